@@ -16,7 +16,18 @@ const COMMANDS: &[&str] = &[
     "register_photokit_permission_listener",
     "unregister_photokit_permission_listener",
     "get_photokit_permission_listeners",
+    "register_permission_listener",
+    "unregister_permission_listener",
+    "get_permission_listeners",
+    "pause_permission_listener",
+    "resume_permission_listener",
+    "get_permission_event_history",
+    "get_scheduler_stats",
+    "watch_permission",
     "get_photos_count",
+    "check_permission",
+    "request_permission",
+    "check_all_permissions",
 ];
 
 fn main() {
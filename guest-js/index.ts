@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 
 export const COMMAND = {
     CHECK_ACCESSIBILITY_PERMISSION:
@@ -34,13 +34,30 @@ export const COMMAND = {
         "plugin:macos-permissions-with-photokit|unregister_photokit_permission_listener",
     GET_PHOTOKIT_PERMISSION_LISTENERS:
         "plugin:macos-permissions-with-photokit|get_photokit_permission_listeners",
+    REGISTER_PERMISSION_LISTENER:
+        "plugin:macos-permissions-with-photokit|register_permission_listener",
+    UNREGISTER_PERMISSION_LISTENER:
+        "plugin:macos-permissions-with-photokit|unregister_permission_listener",
+    GET_PERMISSION_LISTENERS: "plugin:macos-permissions-with-photokit|get_permission_listeners",
+    PAUSE_PERMISSION_LISTENER:
+        "plugin:macos-permissions-with-photokit|pause_permission_listener",
+    RESUME_PERMISSION_LISTENER:
+        "plugin:macos-permissions-with-photokit|resume_permission_listener",
+    GET_PERMISSION_EVENT_HISTORY:
+        "plugin:macos-permissions-with-photokit|get_permission_event_history",
+    GET_SCHEDULER_STATS: "plugin:macos-permissions-with-photokit|get_scheduler_stats",
+    WATCH_PERMISSION: "plugin:macos-permissions-with-photokit|watch_permission",
     GET_PHOTOS_COUNT: "plugin:macos-permissions-with-photokit|get_photos_count",
+    CHECK_PERMISSION: "plugin:macos-permissions-with-photokit|check_permission",
+    REQUEST_PERMISSION: "plugin:macos-permissions-with-photokit|request_permission",
+    CHECK_ALL_PERMISSIONS: "plugin:macos-permissions-with-photokit|check_all_permissions",
 };
 
 /**
  * Check accessibility permission.
  *
- * @returns `true` if accessibility permission are granted, `false` otherwise.
+ * @returns `true` if accessibility permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkAccessibilityPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -67,7 +84,8 @@ export const requestAccessibilityPermission = () => {
 /**
  * Check full disk access permission.
  *
- * @returns `true` if full disk access permission are granted, `false` otherwise.
+ * @returns `true` if full disk access permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkFullDiskAccessPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -94,7 +112,8 @@ export const requestFullDiskAccessPermission = () => {
 /**
  * Check screen recording permission.
  *
- * @returns `true` if screen recording permission are granted, `false` otherwise.
+ * @returns `true` if screen recording permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkScreenRecordingPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -121,7 +140,8 @@ export const requestScreenRecordingPermission = () => {
 /**
  * Check microphone permission.
  *
- * @returns `true` if microphone permission are granted, `false` otherwise.
+ * @returns `true` if microphone permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkMicrophonePermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -139,16 +159,18 @@ export const checkMicrophonePermission = () => {
  * @example
  * import { requestMicrophonePermission } from "tauri-plugin-macos-permissions-with-photokit-api";
  *
- * await requestMicrophonePermission();
+ * const status = await requestMicrophonePermission();
+ * console.log(status); // "authorized" | "denied" | ...
  */
 export const requestMicrophonePermission = () => {
-    return invoke(COMMAND.REQUEST_MICROPHONE_PERMISSION);
+    return invoke<PermissionStatus>(COMMAND.REQUEST_MICROPHONE_PERMISSION);
 };
 
 /**
  * Check camera permission.
  *
- * @returns `true` if camera permission are granted, `false` otherwise.
+ * @returns `true` if camera permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkCameraPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -166,16 +188,18 @@ export const checkCameraPermission = () => {
  * @example
  * import { requestCameraPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
  *
- * await requestCameraPermission();
+ * const status = await requestCameraPermission();
+ * console.log(status); // "authorized" | "denied" | ...
  */
 export const requestCameraPermission = () => {
-    return invoke(COMMAND.REQUEST_CAMERA_PERMISSION);
+    return invoke<PermissionStatus>(COMMAND.REQUEST_CAMERA_PERMISSION);
 };
 
 /**
  * Check input monitoring permission.
  *
- * @returns `true` if input monitoring permission are granted, `false` otherwise.
+ * @returns `true` if input monitoring permission is granted, `false` otherwise.
+ * @throws {PermissionError} If the permission is disabled or cannot be checked on this platform.
  *
  * @example
  * import { checkInputMonitoringPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -215,13 +239,164 @@ export type PhotoKitAuthorizationStatus =
     | "limited";
 
 /**
- * PhotoKit permission listener information.
+ * Every permission supported by the plugin.
+ */
+export type Permission =
+    | "accessibility"
+    | "fullDiskAccess"
+    | "screenRecording"
+    | "microphone"
+    | "camera"
+    | "inputMonitoring"
+    | { photoKit: PhotoKitAccessLevel };
+
+/**
+ * Authorization status shared by every permission.
+ *
+ * `notDetermined` means the user has never been asked, `denied` means the user has to
+ * change it in System Settings. `limited` is only reported for PhotoKit.
+ */
+export type PermissionStatus =
+    | "notDetermined"
+    | "restricted"
+    | "denied"
+    | "authorized"
+    | "limited";
+
+/**
+ * Error returned by every plugin command that can fail.
+ *
+ * Branch on `code` instead of the message, which is localized.
+ *
+ * @example
+ * try {
+ *   await requestCameraPermission();
+ * } catch (error) {
+ *   const { code } = error as PermissionError;
+ *   if (code === "missingUsageDescription") {
+ *     // add NSCameraUsageDescription to Info.plist
+ *   }
+ * }
+ */
+export type PermissionError =
+    | { code: "platformUnsupported"; feature: string }
+    | { code: "frameworkUnavailable"; framework: string }
+    | { code: "missingUsageDescription"; permission: Permission; key: string }
+    | { code: "timeout"; permission: Permission | null; timeoutMs: number }
+    | { code: "requestFailed"; permission: Permission | null; message: string }
+    | { code: "permissionDisabled"; permission: Permission }
+    | { code: "lockPoisoned"; resource: string };
+
+/**
+ * Stable machine code of a `PermissionError`.
+ */
+export type PermissionErrorCode = PermissionError["code"];
+
+/**
+ * Status of every enabled permission at one point in time.
+ */
+export interface PermissionSnapshot {
+    statuses: { permission: Permission; status: PermissionStatus }[];
+    /** Permissions whose check did not finish within the probe timeout. */
+    timedOut: Permission[];
+    /** Permissions whose check failed. */
+    failed: Permission[];
+    /** Unix timestamp in milliseconds. */
+    capturedAt: number;
+}
+
+/**
+ * Where a listener's change events are sent, as stored on `ListenerInfo`.
+ */
+export type EmitTarget =
+    | { webview: string }
+    | { labels: string[] }
+    | { channel: number }
+    | "broadcast";
+
+/**
+ * Where a listener's change events should be sent, chosen at registration.
+ *
+ * `"caller"` is the webview that registers the listener. When omitted, the
+ * plugin's configured `eventTargets` are used.
+ */
+export type ListenerTarget =
+    | "caller"
+    | { labels: string[] }
+    | "broadcast";
+
+/**
+ * Which status changes a listener receives.
+ *
+ * - `onGrant`: the status became `authorized` or `limited` from anything else
+ * - `onRevoke`: the status left `authorized` or `limited`
+ * - `onAnyChange`: every change (the default)
+ * - `{ statuses }`: the status became one of the listed statuses
+ */
+export type TransitionFilter =
+    | "onGrant"
+    | "onRevoke"
+    | "onAnyChange"
+    | { statuses: PermissionStatus[] };
+
+/**
+ * Permission listener information.
  */
 export interface ListenerInfo {
     id: string;
-    access_level: PhotoKitAccessLevel;
+    permission: Permission;
+    /** Same as `permission.photoKit`, kept for compatibility; omitted for other permissions. */
+    access_level?: PhotoKitAccessLevel;
+    target: EmitTarget;
     created_at: number;
+    /** `false` while the listener is paused. */
     active: boolean;
+    /** Label of the webview that registered the listener, `null` for listeners registered from Rust. */
+    owner: string | null;
+    filter: TransitionFilter;
+}
+
+/**
+ * Why a permission status changed.
+ *
+ * - `userRequest`: a request made through this plugin was answered
+ * - `externalChange`: the status changed outside the plugin, e.g. in System Settings
+ * - `startupReconciliation`: the status differs from the one saved by the previous launch
+ * - `initial`: the current status, sent once to a listener when it registers
+ */
+export type ChangeCause = "userRequest" | "externalChange" | "startupReconciliation" | "initial";
+
+/**
+ * Payload of the `permission-changed` event.
+ */
+export interface PermissionChangedEvent {
+    permission: Permission;
+    /** Status before the change, `null` if none was observed yet. */
+    previousStatus: PermissionStatus | null;
+    status: PermissionStatus;
+    cause: ChangeCause;
+    /** Increases with every event of a session; use it to order events with equal timestamps. */
+    sequence: number;
+    /** Unix timestamp in milliseconds. */
+    timestamp: number;
+}
+
+/**
+ * Statistics of the scheduler that polls watched permissions for changes.
+ */
+export interface SchedulerStats {
+    /** Whether the background detection thread is running. */
+    running: boolean;
+    /** Number of distinct permissions with an active listener. */
+    watchedPermissions: number;
+    /** Current polling interval in milliseconds, `null` before the first poll. */
+    currentIntervalMs: number | null;
+    /** Unix timestamp in milliseconds of the last poll. */
+    lastPollAt: number | null;
+    /** Unix timestamp in milliseconds of the next poll, `null` if it is due now. */
+    nextPollAt: number | null;
+    polls: number;
+    changesDetected: number;
 }
 
 /**
@@ -270,28 +445,41 @@ export const requestPhotoKitPermission = (accessLevel: PhotoKitAccessLevel): Pro
  * Register a PhotoKit permission status listener.
  *
  * This creates a listener that will emit events when the PhotoKit permission status changes
- * for the specified access level.
+ * for the specified access level. The current status is sent right away with
+ * `cause: "initial"`, so call `listen` before registering. The `initial` event
+ * only goes to this webview, even when `target` names other windows or
+ * broadcasts; later changes go to `target`.
  *
  * @param accessLevel - The PhotoKit access level to monitor ('read' | 'readWrite' | 'addOnly')
+ * @param target - Where to send change events, defaults to the configured `eventTargets`
+ * @param filter - Which changes to deliver, defaults to `"onAnyChange"`
  * @returns The listener ID on success
  *
  * @example
  * import { registerPhotoKitPermissionListener } from "tauri-plugin-macos-permissions-with-photokit-api";
  * import { listen } from '@tauri-apps/api/event';
  *
- * // 注册监听器
- * const listenerId = await registerPhotoKitPermissionListener('read');
- *
- * // 监听权限状态变化事件
+ * // 监听权限状态变化事件，注册时会先收到当前状态
  * const unlisten = await listen('photokit-permission-changed', (event) => {
  *   console.log('权限状态变化:', event.payload);
  * });
  *
+ * // 注册监听器
+ * const listenerId = await registerPhotoKitPermissionListener('read');
+ *
  * // 稍后注销监听器
  * await unregisterPhotoKitPermissionListener(listenerId);
  */
-export const registerPhotoKitPermissionListener = (accessLevel: PhotoKitAccessLevel): Promise<string> => {
-    return invoke<string>(COMMAND.REGISTER_PHOTOKIT_PERMISSION_LISTENER, { accessLevel });
+export const registerPhotoKitPermissionListener = (
+    accessLevel: PhotoKitAccessLevel,
+    target?: ListenerTarget,
+    filter?: TransitionFilter,
+): Promise<string> => {
+    return invoke<string>(COMMAND.REGISTER_PHOTOKIT_PERMISSION_LISTENER, {
+        accessLevel,
+        target,
+        filter,
+    });
 };
 
 /**
@@ -317,11 +505,12 @@ export const unregisterPhotoKitPermissionListener = (listenerId: string): Promis
 };
 
 /**
- * Get all active PhotoKit permission listeners.
+ * Get all PhotoKit permission listeners.
  *
- * This returns information about all currently registered permission listeners.
+ * This returns information about all currently registered PhotoKit permission listeners.
+ * Paused listeners are included with `active` set to `false`.
  *
- * @returns List of active listeners
+ * @returns List of listeners
  *
  * @example
  * import { getPhotoKitPermissionListeners } from "tauri-plugin-macos-permissions-with-photokit-api";
@@ -354,3 +543,207 @@ export const getPhotoKitPermissionListeners = (): Promise<ListenerInfo[]> => {
 export const getPhotosCount = (): Promise<number> => {
     return invoke<number>(COMMAND.GET_PHOTOS_COUNT);
 };
+
+/**
+ * Check the authorization status of the given permission.
+ *
+ * @param permission - The permission to check
+ * @returns The current authorization status
+ *
+ * @example
+ * import { checkPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const status = await checkPermission("camera");
+ * if (status === "notDetermined") {
+ *   // show the prompt
+ * } else if (status === "denied") {
+ *   // send the user to System Settings
+ * }
+ */
+export const checkPermission = (permission: Permission): Promise<PermissionStatus> => {
+    return invoke<PermissionStatus>(COMMAND.CHECK_PERMISSION, { permission });
+};
+
+/**
+ * Request the given permission.
+ *
+ * @param permission - The permission to request
+ * @returns The authorization status after the request
+ *
+ * @example
+ * import { requestPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const status = await requestPermission({ photoKit: "readWrite" });
+ * console.log(status); // "authorized"
+ */
+export const requestPermission = (permission: Permission): Promise<PermissionStatus> => {
+    return invoke<PermissionStatus>(COMMAND.REQUEST_PERMISSION, { permission });
+};
+
+/**
+ * Check every enabled permission at once.
+ *
+ * @returns A snapshot with the status of every enabled permission
+ *
+ * @example
+ * import { checkAllPermissions } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const snapshot = await checkAllPermissions();
+ * for (const { permission, status } of snapshot.statuses) {
+ *   console.log(permission, status);
+ * }
+ */
+export const checkAllPermissions = (): Promise<PermissionSnapshot> => {
+    return invoke<PermissionSnapshot>(COMMAND.CHECK_ALL_PERMISSIONS);
+};
+
+/**
+ * Register a listener for status changes of any permission.
+ *
+ * While registered, the plugin emits a `permission-changed` event whenever the
+ * status of the permission changes. The listener is removed automatically when
+ * this webview is destroyed or reloaded. The current status is sent right away
+ * with `cause: "initial"`, so call `listen` before registering. The `initial`
+ * event only goes to this webview, even when `target` names other windows or
+ * broadcasts; later changes go to `target`.
+ *
+ * @param permission - The permission to monitor
+ * @param target - Where to send change events, defaults to the configured `eventTargets`
+ * @param filter - Which changes to deliver, defaults to `"onAnyChange"`
+ * @returns The listener ID on success
+ *
+ * @example
+ * import { listen } from "@tauri-apps/api/event";
+ * import { registerPermissionListener, type PermissionChangedEvent } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const unlisten = await listen<PermissionChangedEvent>("permission-changed", (event) => {
+ *   console.log(event.payload.permission, event.payload.status);
+ * });
+ * const listenerId = await registerPermissionListener("accessibility", "caller");
+ */
+export const registerPermissionListener = (
+    permission: Permission,
+    target?: ListenerTarget,
+    filter?: TransitionFilter,
+): Promise<string> => {
+    return invoke<string>(COMMAND.REGISTER_PERMISSION_LISTENER, { permission, target, filter });
+};
+
+/**
+ * Unregister a listener returned by `registerPermissionListener`.
+ *
+ * @param listenerId - The ID of the listener to unregister
+ */
+export const unregisterPermissionListener = (listenerId: string): Promise<void> => {
+    return invoke<void>(COMMAND.UNREGISTER_PERMISSION_LISTENER, { listenerId });
+};
+
+/**
+ * Get all permission listeners, including PhotoKit listeners.
+ *
+ * Paused listeners are included with `active` set to `false`.
+ *
+ * @returns List of listeners
+ */
+export const getPermissionListeners = (): Promise<ListenerInfo[]> => {
+    return invoke<ListenerInfo[]>(COMMAND.GET_PERMISSION_LISTENERS);
+};
+
+/**
+ * Pause a permission listener.
+ *
+ * A paused listener stays registered but receives no change events until it
+ * is resumed. Changes that happen while it is paused are not replayed.
+ *
+ * @param listenerId - The ID of the listener to pause
+ *
+ * @example
+ * import { pausePermissionListener, resumePermissionListener } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * await pausePermissionListener(listenerId);
+ * await runOnboarding();
+ * await resumePermissionListener(listenerId);
+ */
+export const pausePermissionListener = (listenerId: string): Promise<void> => {
+    return invoke<void>(COMMAND.PAUSE_PERMISSION_LISTENER, { listenerId });
+};
+
+/**
+ * Resume a listener paused with `pausePermissionListener`.
+ *
+ * @param listenerId - The ID of the listener to resume
+ */
+export const resumePermissionListener = (listenerId: string): Promise<void> => {
+    return invoke<void>(COMMAND.RESUME_PERMISSION_LISTENER, { listenerId });
+};
+
+/**
+ * Get the permission change events of the current session.
+ *
+ * The plugin keeps the most recent `historyCapacity` change events in memory.
+ * The `initial` events sent to newly registered listeners are not recorded.
+ *
+ * @param since - Only return events at or after this Unix timestamp in milliseconds
+ * @param permission - Only return events of this permission
+ * @returns The matching events, oldest first
+ *
+ * @example
+ * import { getPermissionEventHistory } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const history = await getPermissionEventHistory(0, { photoKit: "read" });
+ */
+export const getPermissionEventHistory = (
+    since: number,
+    permission?: Permission,
+): Promise<PermissionChangedEvent[]> => {
+    return invoke<PermissionChangedEvent[]>(COMMAND.GET_PERMISSION_EVENT_HISTORY, {
+        since,
+        permission,
+    });
+};
+
+/**
+ * Get statistics of the scheduler that polls watched permissions for changes.
+ *
+ * @returns Whether the scheduler runs, its current interval and its poll counts
+ *
+ * @example
+ * import { getSchedulerStats } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * const stats = await getSchedulerStats();
+ * console.log(stats.currentIntervalMs, stats.polls);
+ */
+export const getSchedulerStats = (): Promise<SchedulerStats> => {
+    return invoke<SchedulerStats>(COMMAND.GET_SCHEDULER_STATS);
+};
+
+/**
+ * Stream status changes of a permission to a callback.
+ *
+ * `onChange` is first called with the current status (`cause: "initial"`).
+ * The subscription is tied to a channel instead of a global event, so it ends
+ * automatically when this webview is destroyed or reloaded.
+ *
+ * @param permission - The permission to watch
+ * @param onChange - Called with every status change
+ * @param filter - Which changes to deliver, defaults to `"onAnyChange"`
+ * @returns The listener ID, which can be passed to `unregisterPermissionListener` to stop early
+ *
+ * @example
+ * import { watchPermission } from "tauri-plugin-macos-permissions-with-photokit-api";
+ *
+ * await watchPermission("screenRecording", (event) => {
+ *   if (event.status === "denied") {
+ *     stopCapture();
+ *   }
+ * });
+ */
+export const watchPermission = (
+    permission: Permission,
+    onChange: (event: PermissionChangedEvent) => void,
+    filter?: TransitionFilter,
+): Promise<string> => {
+    const channel = new Channel<PermissionChangedEvent>();
+    channel.onmessage = onChange;
+    return invoke<string>(COMMAND.WATCH_PERMISSION, { permission, channel, filter });
+};
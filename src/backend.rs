@@ -0,0 +1,350 @@
+//! 权限后端抽象
+//!
+//! 此模块定义了 `PermissionBackend` trait，它覆盖了插件支持的所有权限检查与请求操作。
+//! 插件命令不再直接调用系统 API，而是通过 `init_with_backend` 注入的后端完成实际工作，
+//! 这样就可以在 macOS 系统实现与内存模拟实现（见 `FakePermissionBackend`）之间切换。
+
+use crate::{
+    FallbackPolicy, Permission, PermissionError, PermissionStatus, PhotoKitAccessLevel,
+    PhotoKitAuthorizationStatus, PhotoKitManagerError,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 等待用户响应系统权限对话框的最长时间
+///
+/// 超过此时间仍未收到完成回调时，请求会以超时错误结束。
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 在插件状态中共享的权限后端
+pub type SharedPermissionBackend = Arc<dyn PermissionBackend>;
+
+/// 权限后端
+///
+/// 每个方法对应一项系统权限的检查或请求操作。
+/// 实现必须是线程安全的，因为插件命令会在异步运行时的任意线程上调用它们。
+pub trait PermissionBackend: Send + Sync {
+    /// 检查辅助功能权限
+    ///
+    /// macOS 只提供“是否受信任”的布尔结果，因此只会返回 `Authorized` 或 `Denied`。
+    fn check_accessibility(&self) -> PermissionStatus;
+
+    /// 请求辅助功能权限
+    fn request_accessibility(&self);
+
+    /// 检查完全磁盘访问权限
+    fn check_full_disk_access(&self) -> PermissionStatus;
+
+    /// 请求完全磁盘访问权限（打开系统设置）
+    fn request_full_disk_access(&self) -> Result<(), PermissionError>;
+
+    /// 检查屏幕录制权限
+    fn check_screen_recording(&self) -> PermissionStatus;
+
+    /// 请求屏幕录制权限
+    fn request_screen_recording(&self);
+
+    /// 检查麦克风权限
+    fn check_microphone(&self) -> PermissionStatus;
+
+    /// 请求麦克风权限，等待用户响应后返回最终的授权状态
+    fn request_microphone(&self) -> Result<PermissionStatus, PermissionError>;
+
+    /// 检查摄像头权限
+    fn check_camera(&self) -> PermissionStatus;
+
+    /// 请求摄像头权限，等待用户响应后返回最终的授权状态
+    fn request_camera(&self) -> Result<PermissionStatus, PermissionError>;
+
+    /// 检查输入监控权限
+    fn check_input_monitoring(&self) -> PermissionStatus;
+
+    /// 请求输入监控权限（打开系统设置）
+    fn request_input_monitoring(&self) -> Result<(), PermissionError>;
+
+    /// 检查指定权限级别的 PhotoKit 授权状态
+    fn check_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError>;
+
+    /// 请求指定权限级别的 PhotoKit 授权，等待用户响应后返回最终的授权状态
+    fn request_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError>;
+
+    /// PhotoKit 框架是否可用
+    fn is_photokit_available(&self) -> bool;
+
+    /// 获取照片库中的总照片数量
+    fn get_photos_count(&self) -> Result<u64, PhotoKitManagerError>;
+
+    /// 确认当前后端可以处理指定权限
+    ///
+    /// 权限管理器在每次检查和请求之前调用此方法，默认实现总是返回 `Ok`。
+    fn ensure_supported(&self, _permission: Permission) -> Result<(), PermissionError> {
+        Ok(())
+    }
+}
+
+/// 非 macOS 平台使用的后端
+///
+/// 没有系统权限 API 可用，所有检查和请求的结果由 `FallbackPolicy` 决定。
+#[cfg(not(target_os = "macos"))]
+#[derive(Debug, Default)]
+pub struct FallbackPermissionBackend {
+    policy: FallbackPolicy,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl FallbackPermissionBackend {
+    /// 创建使用指定回退策略的后端
+    pub fn new(policy: FallbackPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// 当前策略下指定权限的状态
+    fn status(&self, permission: Permission) -> PermissionStatus {
+        match &self.policy {
+            FallbackPolicy::AssumeGranted => PermissionStatus::Authorized,
+            FallbackPolicy::AssumeDenied => PermissionStatus::Denied,
+            FallbackPolicy::UnsupportedError => PermissionStatus::NotDetermined,
+            FallbackPolicy::Scripted(statuses) => statuses
+                .get(&permission.kind())
+                .copied()
+                .unwrap_or(PermissionStatus::NotDetermined),
+        }
+    }
+
+    /// 当前策略下的请求结果
+    fn request(&self, permission: Permission) -> Result<PermissionStatus, PermissionError> {
+        self.ensure_supported(permission)?;
+        Ok(self.status(permission))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl PermissionBackend for FallbackPermissionBackend {
+    fn check_accessibility(&self) -> PermissionStatus {
+        self.status(Permission::Accessibility)
+    }
+
+    fn request_accessibility(&self) {}
+
+    fn check_full_disk_access(&self) -> PermissionStatus {
+        self.status(Permission::FullDiskAccess)
+    }
+
+    fn request_full_disk_access(&self) -> Result<(), PermissionError> {
+        self.request(Permission::FullDiskAccess).map(|_| ())
+    }
+
+    fn check_screen_recording(&self) -> PermissionStatus {
+        self.status(Permission::ScreenRecording)
+    }
+
+    fn request_screen_recording(&self) {}
+
+    fn check_microphone(&self) -> PermissionStatus {
+        self.status(Permission::Microphone)
+    }
+
+    fn request_microphone(&self) -> Result<PermissionStatus, PermissionError> {
+        self.request(Permission::Microphone)
+    }
+
+    fn check_camera(&self) -> PermissionStatus {
+        self.status(Permission::Camera)
+    }
+
+    fn request_camera(&self) -> Result<PermissionStatus, PermissionError> {
+        self.request(Permission::Camera)
+    }
+
+    fn check_input_monitoring(&self) -> PermissionStatus {
+        self.status(Permission::InputMonitoring)
+    }
+
+    fn request_input_monitoring(&self) -> Result<(), PermissionError> {
+        self.request(Permission::InputMonitoring).map(|_| ())
+    }
+
+    fn check_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        let permission = Permission::PhotoKit(access_level);
+        self.ensure_supported(permission)?;
+        Ok(self.status(permission).into())
+    }
+
+    fn request_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        Ok(self.request(Permission::PhotoKit(access_level))?.into())
+    }
+
+    fn is_photokit_available(&self) -> bool {
+        false
+    }
+
+    fn get_photos_count(&self) -> Result<u64, PhotoKitManagerError> {
+        let permission = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+        self.ensure_supported(permission)?;
+
+        if !self.status(permission).is_granted() {
+            return Err(PhotoKitManagerError::CheckFailed(
+                "需要照片库读取权限才能查询照片数量".to_string(),
+            ));
+        }
+
+        // 没有照片库可以查询
+        Ok(0)
+    }
+
+    fn ensure_supported(&self, permission: Permission) -> Result<(), PermissionError> {
+        match self.policy {
+            FallbackPolicy::UnsupportedError => Err(PermissionError::PlatformUnsupported {
+                feature: format!("{permission:?}"),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 创建当前平台的默认权限后端
+///
+/// 在 macOS 上返回调用系统 API 的 `MacOSPermissionBackend`，
+/// 在其他平台上返回使用默认回退策略的 `FallbackPermissionBackend`。
+pub fn default_backend() -> SharedPermissionBackend {
+    platform_backend(FallbackPolicy::default())
+}
+
+/// 创建当前平台的权限后端
+///
+/// # Arguments
+/// * `fallback_policy` - 非 macOS 平台上使用的回退策略，在 macOS 上被忽略
+#[cfg_attr(target_os = "macos", allow(unused_variables))]
+pub fn platform_backend(fallback_policy: FallbackPolicy) -> SharedPermissionBackend {
+    #[cfg(target_os = "macos")]
+    {
+        Arc::new(crate::MacOSPermissionBackend::new())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Arc::new(FallbackPermissionBackend::new(fallback_policy))
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use crate::{PermissionKind, PhotoKitPermissionManager};
+    use std::collections::HashMap;
+
+    fn manager(policy: FallbackPolicy) -> PhotoKitPermissionManager {
+        PhotoKitPermissionManager::with_backend(
+            Arc::new(FallbackPermissionBackend::new(policy)),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_assume_granted_policy() {
+        let manager = manager(FallbackPolicy::AssumeGranted);
+
+        assert_eq!(
+            manager.check_permission(Permission::Camera).unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(
+            manager
+                .check_authorization_status(PhotoKitAccessLevel::ReadWrite)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Authorized
+        );
+        assert_eq!(
+            manager.request_permission(Permission::Microphone).unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(manager.get_photos_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_assume_denied_policy() {
+        let manager = manager(FallbackPolicy::AssumeDenied);
+
+        assert_eq!(
+            manager.check_permission(Permission::Accessibility).unwrap(),
+            PermissionStatus::Denied
+        );
+        assert_eq!(
+            manager
+                .request_authorization(PhotoKitAccessLevel::Read)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Denied
+        );
+        assert_eq!(
+            manager
+                .request_permission(Permission::ScreenRecording)
+                .unwrap(),
+            PermissionStatus::Denied
+        );
+        assert!(manager.get_photos_count().is_err());
+    }
+
+    #[test]
+    fn test_unsupported_error_policy() {
+        let manager = manager(FallbackPolicy::UnsupportedError);
+
+        for permission in [
+            Permission::Accessibility,
+            Permission::Camera,
+            Permission::PhotoKit(PhotoKitAccessLevel::AddOnly),
+        ] {
+            let error = PermissionError::from(manager.check_permission(permission).unwrap_err());
+            assert!(matches!(error, PermissionError::PlatformUnsupported { .. }));
+
+            let error = PermissionError::from(manager.request_permission(permission).unwrap_err());
+            assert!(matches!(error, PermissionError::PlatformUnsupported { .. }));
+        }
+
+        assert!(manager
+            .check_authorization_status(PhotoKitAccessLevel::Read)
+            .is_err());
+        assert!(manager.get_photos_count().is_err());
+    }
+
+    #[test]
+    fn test_scripted_policy() {
+        let manager = manager(FallbackPolicy::Scripted(HashMap::from([
+            (PermissionKind::Camera, PermissionStatus::Denied),
+            (PermissionKind::PhotoKit, PermissionStatus::Limited),
+        ])));
+
+        assert_eq!(
+            manager.check_permission(Permission::Camera).unwrap(),
+            PermissionStatus::Denied
+        );
+        assert_eq!(
+            manager.check_permission(Permission::Microphone).unwrap(),
+            PermissionStatus::NotDetermined
+        );
+        assert_eq!(
+            manager
+                .check_permission(Permission::PhotoKit(PhotoKitAccessLevel::Read))
+                .unwrap(),
+            PermissionStatus::Limited
+        );
+
+        // 请求不会改变预设的状态
+        assert_eq!(
+            manager.request_permission(Permission::Camera).unwrap(),
+            PermissionStatus::Denied
+        );
+        assert_eq!(manager.get_photos_count().unwrap(), 0);
+    }
+}
@@ -0,0 +1,507 @@
+//! 插件构建器
+//!
+//! `Builder` 在 `tauri.conf.json` 的配置之上提供 Rust 侧的流式配置 API。
+//! 通过构建器设置的选项会覆盖配置文件中的同名字段。
+
+use crate::{
+    commands, platform_backend, reconcile_saved_snapshot, Config, FallbackPolicy, MacosPermissions,
+    MacosPermissionsExt, PermissionKind, PhotoKitPermissionListener, PhotoKitPermissionManager,
+    SharedPermissionBackend, SnapshotStore, SNAPSHOT_FILE_NAME,
+};
+use std::time::Duration;
+use tauri::{
+    generate_handler,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    webview::PageLoadEvent,
+    AppHandle, Manager, RunEvent, Runtime, WindowEvent,
+};
+
+/// 插件名称，同时也是 `tauri.conf.json` 中 `plugins` 下的配置键
+const PLUGIN_NAME: &str = "macos-permissions-with-photokit";
+
+/// 插件构建器
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use tauri_plugin_macos_permissions_with_photokit::{Builder, FallbackPolicy, PermissionKind};
+///
+/// tauri::Builder::default().plugin(
+///     Builder::new()
+///         .cache_ttl(Duration::from_secs(10))
+///         .event_targets(["main", "settings"])
+///         .fallback_policy(FallbackPolicy::AssumeDenied)
+///         .enabled_permissions([PermissionKind::Camera, PermissionKind::PhotoKit])
+///         .build(),
+/// );
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    backend: Option<SharedPermissionBackend>,
+    cache_ttl: Option<u64>,
+    event_targets: Option<Vec<String>>,
+    polling_interval: Option<Option<u64>>,
+    max_polling_interval: Option<u64>,
+    debounce_window: Option<u64>,
+    history_capacity: Option<usize>,
+    persist_snapshot: Option<bool>,
+    fallback_policy: Option<FallbackPolicy>,
+    enabled_permissions: Option<Vec<PermissionKind>>,
+}
+
+impl Builder {
+    /// 创建新的插件构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用指定的权限后端，例如在测试中传入 `FakePermissionBackend`
+    pub fn backend(mut self, backend: SharedPermissionBackend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// 设置 PhotoKit 授权状态缓存的过期时间
+    ///
+    /// 缓存以秒为单位过期，不足一秒的部分向上取整，例如 500 毫秒按 1 秒缓存。
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        let partial_second = u64::from(cache_ttl.subsec_nanos() > 0);
+        self.cache_ttl = Some(cache_ttl.as_secs().saturating_add(partial_second));
+        self
+    }
+
+    /// 设置接收权限变化事件的窗口标签，为空时广播到所有窗口
+    pub fn event_targets<I, S>(mut self, event_targets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.event_targets = Some(event_targets.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// 设置检测权限变化的轮询间隔
+    pub fn polling_interval(mut self, polling_interval: Duration) -> Self {
+        self.polling_interval = Some(Some(polling_interval.as_millis() as u64));
+        self
+    }
+
+    /// 不在后台检测权限变化，相当于配置文件中的 `"pollingInterval": null`
+    ///
+    /// 权限仍会在窗口获得焦点或发起请求时重新检查。
+    pub fn disable_polling(mut self) -> Self {
+        self.polling_interval = Some(None);
+        self
+    }
+
+    /// 设置状态没有变化时轮询间隔逐次翻倍的上限
+    pub fn max_polling_interval(mut self, max_polling_interval: Duration) -> Self {
+        self.max_polling_interval = Some(max_polling_interval.as_millis() as u64);
+        self
+    }
+
+    /// 设置权限状态稳定多久后才发送变化事件，窗口内的连续变化合并为一个事件
+    pub fn debounce_window(mut self, debounce_window: Duration) -> Self {
+        self.debounce_window = Some(debounce_window.as_millis() as u64);
+        self
+    }
+
+    /// 设置最多保留的权限变化历史事件数量，为 0 时不记录
+    pub fn history_capacity(mut self, history_capacity: usize) -> Self {
+        self.history_capacity = Some(history_capacity);
+        self
+    }
+
+    /// 设置是否保存权限快照，并在启动时发送与上次运行相比的变化
+    pub fn persist_snapshot(mut self, persist_snapshot: bool) -> Self {
+        self.persist_snapshot = Some(persist_snapshot);
+        self
+    }
+
+    /// 设置非 macOS 平台上的权限回退策略
+    pub fn fallback_policy(mut self, fallback_policy: FallbackPolicy) -> Self {
+        self.fallback_policy = Some(fallback_policy);
+        self
+    }
+
+    /// 只启用指定类型的权限
+    pub fn enabled_permissions(
+        mut self,
+        enabled_permissions: impl IntoIterator<Item = PermissionKind>,
+    ) -> Self {
+        self.enabled_permissions = Some(enabled_permissions.into_iter().collect());
+        self
+    }
+
+    /// 用构建器中设置的选项覆盖配置文件中的值
+    fn apply(&self, config: &mut Config) {
+        if let Some(cache_ttl) = self.cache_ttl {
+            config.cache_ttl = cache_ttl;
+        }
+        if let Some(event_targets) = &self.event_targets {
+            config.event_targets = event_targets.clone();
+        }
+        if let Some(polling_interval) = self.polling_interval {
+            config.polling_interval = polling_interval;
+        }
+        if let Some(max_polling_interval) = self.max_polling_interval {
+            config.max_polling_interval = Some(max_polling_interval);
+        }
+        if let Some(debounce_window) = self.debounce_window {
+            config.debounce_window = debounce_window;
+        }
+        if let Some(history_capacity) = self.history_capacity {
+            config.history_capacity = history_capacity;
+        }
+        if let Some(persist_snapshot) = self.persist_snapshot {
+            config.persist_snapshot = persist_snapshot;
+        }
+        if let Some(fallback_policy) = &self.fallback_policy {
+            config.fallback_policy = fallback_policy.clone();
+        }
+        if let Some(enabled_permissions) = &self.enabled_permissions {
+            config.enabled_permissions = Some(enabled_permissions.clone());
+        }
+    }
+
+    /// 构建插件
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R, Option<Config>> {
+        PluginBuilder::<R, Option<Config>>::new(PLUGIN_NAME)
+            .invoke_handler(generate_handler![
+                commands::check_accessibility_permission,
+                commands::request_accessibility_permission,
+                commands::check_full_disk_access_permission,
+                commands::request_full_disk_access_permission,
+                commands::check_screen_recording_permission,
+                commands::request_screen_recording_permission,
+                commands::check_microphone_permission,
+                commands::request_microphone_permission,
+                commands::check_camera_permission,
+                commands::request_camera_permission,
+                commands::check_input_monitoring_permission,
+                commands::request_input_monitoring_permission,
+                commands::check_photokit_permission,
+                commands::request_photokit_permission,
+                commands::register_photokit_permission_listener,
+                commands::unregister_photokit_permission_listener,
+                commands::get_photokit_permission_listeners,
+                commands::register_permission_listener,
+                commands::unregister_permission_listener,
+                commands::get_permission_listeners,
+                commands::pause_permission_listener,
+                commands::resume_permission_listener,
+                commands::get_permission_event_history,
+                commands::get_scheduler_stats,
+                commands::watch_permission,
+                commands::get_photos_count,
+                commands::check_permission,
+                commands::request_permission,
+                commands::check_all_permissions
+            ])
+            .setup(move |app, api| {
+                let mut config = api.config().clone().unwrap_or_default();
+                self.apply(&mut config);
+
+                let backend = self
+                    .backend
+                    .clone()
+                    .unwrap_or_else(|| app_backend(app, config.fallback_policy.clone()));
+
+                // 所有命令共享同一个管理器和监听器注册表，保证缓存和监听器在调用之间保持有效
+                let manager = PhotoKitPermissionManager::with_backend(
+                    backend.clone(),
+                    Some(config.cache_ttl),
+                )
+                .with_enabled_permissions(config.enabled_permissions.clone());
+                // 无法确定应用数据目录时不保存快照
+                let snapshot_store = if config.persist_snapshot {
+                    app.path()
+                        .app_data_dir()
+                        .ok()
+                        .map(|dir| SnapshotStore::new(dir.join(SNAPSHOT_FILE_NAME)))
+                } else {
+                    None
+                };
+
+                let mut listener = PhotoKitPermissionListener::with_event_targets(
+                    app.clone(),
+                    config.event_targets.clone(),
+                )
+                .with_polling_interval(config.polling_interval.map(Duration::from_millis))
+                .with_max_polling_interval(config.max_polling_interval.map(Duration::from_millis))
+                .with_debounce_window(Duration::from_millis(config.debounce_window))
+                .with_history_capacity(config.history_capacity);
+                if let Some(snapshot_store) = snapshot_store {
+                    listener = listener.with_snapshot_store(snapshot_store.clone());
+                    app.manage(snapshot_store);
+                }
+
+                app.manage(MacosPermissions::new(manager.clone(), listener.clone()));
+                app.manage(manager);
+                app.manage(listener);
+                app.manage(backend);
+                app.manage(config);
+                Ok(())
+            })
+            .on_page_load(|webview, payload| {
+                // 重新加载后页面中的监听器句柄已失效
+                if payload.event() == PageLoadEvent::Started {
+                    if let Some(listener) = webview.try_state::<PhotoKitPermissionListener<R>>() {
+                        let _ = listener.remove_webview_listeners(webview.label());
+                    }
+                }
+            })
+            .on_event(|app, event| match event {
+                // 窗口销毁后它的 webview 注册的监听器不再有接收者
+                RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Destroyed,
+                    ..
+                } => {
+                    if let Some(listener) = app.try_state::<PhotoKitPermissionListener<R>>() {
+                        let _ = listener.remove_webview_listeners(label);
+                    }
+                }
+                // 用户通常在系统设置中授权后切回应用，此时立即重新检查
+                RunEvent::WindowEvent {
+                    event: WindowEvent::Focused(true),
+                    ..
+                } => recheck_in_background(app),
+                // 点击 Dock 图标重新打开应用，没有可见窗口时不会有焦点事件
+                #[cfg(target_os = "macos")]
+                RunEvent::Reopen { .. } => recheck_in_background(app),
+                // 应用自身的 setup 已完成，Rust 侧订阅者可以收到启动时发现的变化
+                RunEvent::Ready => reconcile_in_background(app),
+                _ => {}
+            })
+            .build()
+    }
+}
+
+/// 创建应用使用的平台权限后端
+///
+/// macOS 上使用 Tauri 解析的用户主目录检查完全磁盘访问权限，与应用其它路径保持一致。
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn app_backend<R: Runtime>(
+    app: &AppHandle<R>,
+    fallback_policy: FallbackPolicy,
+) -> SharedPermissionBackend {
+    #[cfg(target_os = "macos")]
+    if let Ok(home_dir) = app.path().home_dir() {
+        return std::sync::Arc::new(crate::MacOSPermissionBackend::with_home_dir(home_dir));
+    }
+
+    platform_backend(fallback_policy)
+}
+
+/// 在后台线程上重新检查被监听的权限，避免阻塞事件循环
+///
+/// 上一次重新检查还没完成时跳过，窗口频繁切换焦点时不会堆积检查。
+fn recheck_in_background<R: Runtime>(app: &AppHandle<R>) {
+    let Some(in_flight) = app
+        .try_state::<MacosPermissions<R>>()
+        .and_then(|permissions| permissions.begin_background_recheck())
+    else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let _in_flight = in_flight;
+        let _ = app.macos_permissions().recheck();
+    });
+}
+
+/// 在后台线程上与上次保存的快照比较，避免阻塞应用启动
+fn reconcile_in_background<R: Runtime>(app: &AppHandle<R>) {
+    if app.try_state::<SnapshotStore>().is_none() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let _ = reconcile_saved_snapshot(
+            &app.state::<PhotoKitPermissionManager>(),
+            &app.state::<PhotoKitPermissionListener<R>>(),
+            &app.state::<SnapshotStore>(),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FakePermissionBackend, Permission, PermissionError, PermissionStatus, PhotoKitAccessLevel,
+    };
+    use std::sync::Arc;
+    use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+
+    /// 使用给定的插件配置段和构建器构建测试应用
+    fn mock_app(plugin_config: serde_json::Value, builder: Builder) -> tauri::App<MockRuntime> {
+        let mut context = mock_context(noop_assets());
+        context
+            .config_mut()
+            .plugins
+            .0
+            .insert(PLUGIN_NAME.to_string(), plugin_config);
+
+        mock_builder()
+            .plugin(builder.build())
+            .build(context)
+            .expect("failed to build mock app")
+    }
+
+    #[test]
+    fn test_config_is_read_from_tauri_conf() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_app(
+            serde_json::json!({
+                "cacheTtl": 5,
+                "eventTargets": ["settings"],
+                "enabledPermissions": ["camera"]
+            }),
+            Builder::new().backend(backend.clone()),
+        );
+
+        let config = app.state::<Config>();
+        assert_eq!(config.cache_ttl, 5);
+        assert_eq!(config.event_targets, vec!["settings"]);
+
+        let manager = app.state::<PhotoKitPermissionManager>();
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        assert_eq!(
+            manager.check_permission(Permission::Camera).unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert!(matches!(
+            PermissionError::from(
+                manager
+                    .check_permission(Permission::Microphone)
+                    .unwrap_err()
+            ),
+            PermissionError::PermissionDisabled {
+                permission: Permission::Microphone
+            }
+        ));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[tokio::test]
+    async fn test_commands_respect_fallback_policy() {
+        use crate::{check_camera_permission, check_permission, get_photos_count};
+
+        let app = mock_app(
+            serde_json::json!({ "fallbackPolicy": "assumeDenied" }),
+            Builder::new(),
+        );
+        assert_eq!(
+            check_camera_permission(app.handle().clone()).await,
+            Ok(false)
+        );
+        assert_eq!(
+            check_permission(app.handle().clone(), Permission::ScreenRecording).await,
+            Ok(PermissionStatus::Denied)
+        );
+
+        let app = mock_app(
+            serde_json::json!({}),
+            Builder::new().fallback_policy(FallbackPolicy::UnsupportedError),
+        );
+        // 无法检查时返回错误，而不是当作未授权
+        assert!(matches!(
+            check_camera_permission(app.handle().clone()).await,
+            Err(PermissionError::PlatformUnsupported { .. })
+        ));
+        assert!(matches!(
+            get_photos_count(app.handle().clone()).await,
+            Err(PermissionError::PlatformUnsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_builder_overrides_tauri_conf() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_app(
+            serde_json::json!({
+                "cacheTtl": 5,
+                "persistSnapshot": true,
+                "enabledPermissions": ["camera"]
+            }),
+            Builder::new()
+                .backend(backend)
+                .cache_ttl(Duration::from_secs(60))
+                .polling_interval(Duration::from_millis(500))
+                .max_polling_interval(Duration::from_secs(8))
+                .debounce_window(Duration::from_millis(300))
+                .history_capacity(10)
+                .persist_snapshot(false)
+                .enabled_permissions([PermissionKind::PhotoKit]),
+        );
+
+        let config = app.state::<Config>();
+        assert_eq!(config.cache_ttl, 60);
+        assert_eq!(config.polling_interval, Some(500));
+        assert_eq!(config.max_polling_interval, Some(8_000));
+        assert_eq!(config.debounce_window, 300);
+        assert_eq!(config.history_capacity, 10);
+        assert!(!config.persist_snapshot);
+        assert!(app.try_state::<SnapshotStore>().is_none());
+        assert_eq!(config.event_targets, vec!["main"]);
+
+        let manager = app.state::<PhotoKitPermissionManager>();
+        assert!(manager.check_permission(Permission::Camera).is_err());
+        assert!(manager
+            .check_authorization_status(PhotoKitAccessLevel::Read)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_builder_rounds_cache_ttl_up() {
+        let mut config = Config::default();
+        Builder::new()
+            .cache_ttl(Duration::from_millis(500))
+            .apply(&mut config);
+        assert_eq!(config.cache_ttl, 1);
+
+        Builder::new()
+            .cache_ttl(Duration::from_millis(2_001))
+            .apply(&mut config);
+        assert_eq!(config.cache_ttl, 3);
+
+        Builder::new()
+            .cache_ttl(Duration::from_secs(2))
+            .apply(&mut config);
+        assert_eq!(config.cache_ttl, 2);
+    }
+
+    #[test]
+    fn test_snapshot_persisted_by_default() {
+        let app = mock_app(
+            serde_json::json!({}),
+            Builder::new().backend(Arc::new(FakePermissionBackend::new())),
+        );
+
+        assert!(app.state::<Config>().persist_snapshot);
+        let store = app.state::<SnapshotStore>();
+        assert!(store.path().ends_with(SNAPSHOT_FILE_NAME));
+    }
+
+    #[test]
+    fn test_builder_disables_polling() {
+        let app = mock_app(
+            serde_json::json!({ "pollingInterval": 1000 }),
+            Builder::new()
+                .backend(Arc::new(FakePermissionBackend::new()))
+                .disable_polling(),
+        );
+        assert_eq!(app.state::<Config>().polling_interval, None);
+
+        // 注册监听器不会启动后台检测
+        let listener = app.state::<PhotoKitPermissionListener<tauri::test::MockRuntime>>();
+        listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+        assert!(!listener.scheduler_stats().unwrap().running);
+    }
+}
@@ -0,0 +1,836 @@
+//! 权限变化检测
+//!
+//! macOS 没有提供权限授权状态变化的系统通知，因此 `ChangeDetector` 定期检查每个被监听的
+//! 权限，交给 `PhotoKitPermissionListener::observe_status` 与上一次观察到的状态比较，
+//! 并在状态变化时把事件发送到前端。应用重新获得焦点时插件还会通过
+//! `probe_watched_permissions` 立即检查一次，用户在系统设置中授权后切回应用即可收到变化事件。
+//!
+//! 所有被监听的权限共用一个检测线程，由监听器持有的 `Scheduler` 决定何时检查：
+//! 状态没有变化时检查间隔逐次翻倍，直到最长间隔；检测到变化、发起权限请求或应用获得焦点后
+//! 立即检查并恢复到基础间隔；没有监听器时线程退出。
+
+use crate::{
+    ChangeCause, Clock, Permission, PhotoKitListenerError, PhotoKitPermissionListener,
+    PhotoKitPermissionManager, SharedClock,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::Runtime;
+
+/// 检测线程的调度状态
+#[derive(Debug, Default)]
+struct Schedule {
+    /// 检测线程是否正在运行
+    running: bool,
+    /// 当前的检查间隔，为 None 时下一次使用基础间隔
+    interval: Option<Duration>,
+    /// 下一次检查的时间（Unix 时间戳，毫秒），为 None 时立即检查
+    next_poll: Option<u64>,
+    /// 上一次检查的时间（Unix 时间戳，毫秒）
+    last_poll: Option<u64>,
+    /// 检查次数
+    polls: u64,
+    /// 检测到的状态变化次数
+    changes: u64,
+}
+
+/// 调度器统计信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchedulerStats {
+    /// 检测线程是否正在运行
+    #[serde(rename = "running")]
+    pub running: bool,
+    /// 被监听的权限数量
+    #[serde(rename = "watchedPermissions")]
+    pub watched_permissions: usize,
+    /// 当前的检查间隔（毫秒），还没有检查过时为 None
+    #[serde(rename = "currentIntervalMs")]
+    pub current_interval_ms: Option<u64>,
+    /// 上一次检查的时间（Unix 时间戳，毫秒）
+    #[serde(rename = "lastPollAt")]
+    pub last_poll_at: Option<u64>,
+    /// 下一次检查的时间（Unix 时间戳，毫秒），为 None 时尽快检查
+    #[serde(rename = "nextPollAt")]
+    pub next_poll_at: Option<u64>,
+    /// 检查次数
+    #[serde(rename = "polls")]
+    pub polls: u64,
+    /// 检测到的状态变化次数
+    #[serde(rename = "changesDetected")]
+    pub changes_detected: u64,
+}
+
+/// 所有被监听权限共用的检查调度器
+///
+/// 由监听器持有，检测线程据此决定何时检查，监听器据此在请求或获得焦点后唤醒检测线程。
+#[derive(Debug, Default)]
+pub(crate) struct Scheduler {
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    /// 同一时间只允许一次检查，避免较早开始的检查覆盖较新的状态
+    probe: Mutex<()>,
+}
+
+impl Scheduler {
+    /// 标记检测线程开始运行并重置调度
+    ///
+    /// # Returns
+    /// 需要启动新的检测线程时返回 true；线程已在运行时唤醒它尽快检查并返回 false
+    pub(crate) fn start(&self) -> Result<bool, PhotoKitListenerError> {
+        let mut schedule = self
+            .schedule
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        let started = !schedule.running;
+        schedule.running = true;
+        schedule.interval = None;
+        schedule.next_poll = None;
+        drop(schedule);
+
+        self.wakeup.notify_all();
+        Ok(started)
+    }
+
+    /// 在 `idle` 返回 true 时标记检测线程已停止
+    ///
+    /// 判断和标记在同一把锁内完成，保证与 `start` 并发时不会漏掉新注册的监听器。
+    ///
+    /// # Returns
+    /// 检测线程应当退出时返回 true
+    pub(crate) fn stop_if(&self, idle: impl FnOnce() -> bool) -> bool {
+        let Ok(mut schedule) = self.schedule.lock() else {
+            return true;
+        };
+
+        if idle() {
+            schedule.running = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 确保不晚于指定时间检查，并唤醒检测线程重新计算等待时间
+    pub(crate) fn poll_by(&self, deadline: u64) {
+        if let Ok(mut schedule) = self.schedule.lock() {
+            if let Some(next_poll) = schedule.next_poll {
+                schedule.next_poll = Some(next_poll.min(deadline));
+            }
+        }
+        self.wakeup.notify_all();
+    }
+
+    /// 获取检查锁，检测线程和 `recheck` 的检查按顺序进行
+    fn lock_probe(&self) -> Result<MutexGuard<'_, ()>, PhotoKitListenerError> {
+        self.probe
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)
+    }
+
+    /// 恢复基础间隔并唤醒检测线程立即检查
+    pub(crate) fn poll_soon(&self) {
+        if let Ok(mut schedule) = self.schedule.lock() {
+            schedule.interval = None;
+            schedule.next_poll = None;
+        }
+        self.wakeup.notify_all();
+    }
+
+    /// 检查是否已到下一次检查的时间
+    fn is_due(&self, now: u64) -> Result<bool, PhotoKitListenerError> {
+        let schedule = self
+            .schedule
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        Ok(schedule
+            .next_poll
+            .map_or(true, |next_poll| now >= next_poll))
+    }
+
+    /// 记录一次检查并安排下一次检查
+    ///
+    /// 检测到变化或刚被唤醒时使用基础间隔，否则在上一次间隔的基础上翻倍，不超过最长间隔。
+    /// 还有等待防抖窗口结束的变化时不退避，并且不晚于 `settle_at` 再次检查。
+    fn record_poll(
+        &self,
+        now: u64,
+        changes: usize,
+        base_interval: Duration,
+        max_interval: Duration,
+        settle_at: Option<u64>,
+    ) -> Result<(), PhotoKitListenerError> {
+        let mut schedule = self
+            .schedule
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        let interval = match schedule.interval {
+            Some(interval) if changes == 0 && settle_at.is_some() => interval,
+            Some(interval) if changes == 0 => interval.saturating_mul(2).min(max_interval),
+            _ => base_interval,
+        };
+
+        let next_poll = now.saturating_add(interval.as_millis() as u64);
+        schedule.interval = Some(interval);
+        schedule.next_poll =
+            Some(settle_at.map_or(next_poll, |settle_at| next_poll.min(settle_at)));
+        schedule.last_poll = Some(now);
+        schedule.polls += 1;
+        schedule.changes += changes as u64;
+        Ok(())
+    }
+
+    /// 阻塞到下一次检查的时间或被 `poll_soon` 唤醒
+    fn wait(&self, clock: &dyn Clock) {
+        let Ok(schedule) = self.schedule.lock() else {
+            return;
+        };
+
+        let now = clock.now_millis();
+        let Some(remaining) = schedule
+            .next_poll
+            .map(|next_poll| next_poll.saturating_sub(now))
+            .filter(|&remaining| remaining > 0)
+        else {
+            return;
+        };
+
+        let _ = self
+            .wakeup
+            .wait_timeout(schedule, Duration::from_millis(remaining));
+    }
+
+    /// 获取调度器统计信息
+    ///
+    /// # Arguments
+    /// * `watched_permissions` - 被监听的权限数量
+    pub(crate) fn stats(
+        &self,
+        watched_permissions: usize,
+    ) -> Result<SchedulerStats, PhotoKitListenerError> {
+        let schedule = self
+            .schedule
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        Ok(SchedulerStats {
+            running: schedule.running,
+            watched_permissions,
+            current_interval_ms: schedule
+                .interval
+                .map(|interval| interval.as_millis() as u64),
+            last_poll_at: schedule.last_poll,
+            next_poll_at: schedule.next_poll,
+            polls: schedule.polls,
+            changes_detected: schedule.changes,
+        })
+    }
+}
+
+/// 权限变化检测器
+pub struct ChangeDetector<R: Runtime> {
+    manager: PhotoKitPermissionManager,
+    listener: PhotoKitPermissionListener<R>,
+    clock: SharedClock,
+    interval: Duration,
+    max_interval: Duration,
+}
+
+impl<R: Runtime> ChangeDetector<R> {
+    /// 创建新的权限变化检测器
+    ///
+    /// 默认以固定的间隔检查，通过 `with_max_interval` 启用退避。
+    ///
+    /// # Arguments
+    /// * `manager` - 用于检查权限状态的管理器
+    /// * `listener` - 提供被监听的权限、调度器并分发变化事件的监听器
+    /// * `clock` - 判断检查时间是否到期的时钟
+    /// * `interval` - 基础检查间隔
+    pub fn new(
+        manager: PhotoKitPermissionManager,
+        listener: PhotoKitPermissionListener<R>,
+        clock: SharedClock,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            manager,
+            listener,
+            clock,
+            interval,
+            max_interval: interval,
+        }
+    }
+
+    /// 设置状态没有变化时检查间隔翻倍的上限
+    ///
+    /// # Arguments
+    /// * `max_interval` - 最长检查间隔，小于基础间隔时按基础间隔处理
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval.max(self.interval);
+        self
+    }
+
+    /// 执行一次检测
+    ///
+    /// 如果还没到调度器安排的检查时间，直接返回，否则执行一次 [`probe_watched_permissions`]
+    /// 并安排下一次检查。
+    ///
+    /// # Returns
+    /// 本次检测到的状态变化数量
+    ///
+    /// # Errors
+    /// 如果读取监听器或发送事件失败，返回相应的错误
+    pub fn tick(&self) -> Result<usize, PhotoKitListenerError> {
+        let now = self.clock.now_millis();
+        let scheduler = self.listener.scheduler();
+        if !scheduler.is_due(now)? {
+            return Ok(0);
+        }
+
+        // 检查失败时同样安排下一次检查，避免检测线程空转
+        let result = probe_watched_permissions(&self.manager, &self.listener);
+        let changes = *result.as_ref().unwrap_or(&0);
+        let settle_at = self.listener.pending_deadline()?;
+        scheduler.record_poll(now, changes, self.interval, self.max_interval, settle_at)?;
+
+        result
+    }
+
+    /// 在后台线程上按调度器安排的时间持续检测
+    ///
+    /// 当所有监听器都被注销后线程退出，下次注册监听器时会重新启动。
+    pub fn spawn(self) -> std::io::Result<JoinHandle<()>> {
+        std::thread::Builder::new()
+            .name("permission-change-detector".to_string())
+            .spawn(move || {
+                let _guard = RunningGuard(self.listener.scheduler());
+                loop {
+                    self.listener.scheduler().wait(self.clock.as_ref());
+
+                    if self.listener.release_detector_if_idle() {
+                        break;
+                    }
+
+                    let _ = self.tick();
+                }
+            })
+    }
+}
+
+/// 检测线程因 panic 退出时标记调度器已停止，下次注册监听器时重新启动线程
+struct RunningGuard<'a>(&'a Scheduler);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.stop_if(|| true);
+        }
+    }
+}
+
+/// 绕过状态缓存检查每个被监听的权限，并分发检测到的变化
+///
+/// 第一次看到的权限只记录为基准状态，之后每次状态变化都会以外部变化为原因分发事件。
+/// 设置了防抖窗口时，状态变化要在窗口内保持不变，才会在之后的检查中发送。
+///
+/// # Returns
+/// 检测到的状态变化数量
+///
+/// # Errors
+/// 如果读取监听器或发送事件失败，返回相应的错误
+pub fn probe_watched_permissions<R: Runtime>(
+    manager: &PhotoKitPermissionManager,
+    listener: &PhotoKitPermissionListener<R>,
+) -> Result<usize, PhotoKitListenerError> {
+    // 与其它检查并发时，较早读取的状态可能在较新的状态之后写入，被当作一次变化回退
+    let _probe = listener.scheduler().lock_probe()?;
+
+    let permissions: HashSet<Permission> = listener
+        .get_active_listeners()?
+        .into_iter()
+        .map(|info| info.permission)
+        .collect();
+
+    // 不再被监听的权限重新注册时应重新建立基准状态
+    listener.retain_baselines(&permissions)?;
+
+    let mut changes = 0;
+    for permission in permissions {
+        // 检查失败时保留上一次的状态，下次检测再比较
+        let Ok(status) = manager.refresh_permission(permission) else {
+            continue;
+        };
+
+        if listener.observe_status(permission, status, ChangeCause::ExternalChange)? {
+            changes += 1;
+        }
+    }
+
+    // 发送在防抖窗口内已经稳定的变化
+    changes += listener.flush_pending()?;
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FakePermissionBackend, ManualClock, PermissionStatus, PhotoKitAccessLevel};
+    use std::sync::Arc;
+    use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+    use tauri::Listener;
+
+    const INTERVAL: Duration = Duration::from_secs(2);
+    const DEBOUNCE_WINDOW: Duration = Duration::from_secs(3);
+
+    struct Fixture {
+        _app: tauri::App<MockRuntime>,
+        backend: Arc<FakePermissionBackend>,
+        clock: Arc<ManualClock>,
+        listener: PhotoKitPermissionListener<MockRuntime>,
+        detector: ChangeDetector<MockRuntime>,
+        events: Arc<Mutex<Vec<String>>>,
+        generic_events: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn fixture() -> Fixture {
+        fixture_with_debounce_window(Duration::ZERO)
+    }
+
+    fn fixture_with_debounce_window(debounce_window: Duration) -> Fixture {
+        let app = mock_builder()
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let received = events.clone();
+        app.listen_any("photokit-permission-changed", move |event| {
+            received.lock().unwrap().push(event.payload().to_string());
+        });
+
+        let generic_events = Arc::new(Mutex::new(Vec::new()));
+        let received = generic_events.clone();
+        app.listen_any("permission-changed", move |event| {
+            received.lock().unwrap().push(event.payload().to_string());
+        });
+
+        let backend = Arc::new(FakePermissionBackend::new());
+        let clock = Arc::new(ManualClock::new(1_000));
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+        // 空的目标列表会广播事件，便于在测试中用 listen_any 接收
+        let listener = PhotoKitPermissionListener::with_event_targets(app.handle().clone(), vec![])
+            .with_polling_interval(Some(INTERVAL))
+            .with_clock(clock.clone())
+            .with_debounce_window(debounce_window);
+        let detector = ChangeDetector::new(manager, listener.clone(), clock.clone(), INTERVAL);
+
+        Fixture {
+            _app: app,
+            backend,
+            clock,
+            listener,
+            detector,
+            events,
+            generic_events,
+        }
+    }
+
+    #[test]
+    fn test_detects_transitions_of_subscribed_levels() {
+        let fixture = fixture();
+        let read = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+        fixture
+            .listener
+            .register_listener(PhotoKitAccessLevel::Read)
+            .unwrap();
+
+        // 第一次检测只记录基准状态
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        // 轮询间隔未到期时不检查
+        fixture
+            .backend
+            .set_status(read, PermissionStatus::Authorized);
+        fixture.clock.advance(Duration::from_millis(500));
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+        assert!(fixture.events.lock().unwrap().is_empty());
+
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 1);
+
+        // 状态不变时不重复发送
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture.backend.set_status(read, PermissionStatus::Limited);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 1);
+
+        let events = fixture.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("\"new_status\":\"authorized\""));
+        assert!(events[1].contains("\"new_status\":\"limited\""));
+
+        let first: serde_json::Value = serde_json::from_str(&events[0]).unwrap();
+        let second: serde_json::Value = serde_json::from_str(&events[1]).unwrap();
+        assert_eq!(first["previous_status"], "notDetermined");
+        assert_eq!(second["previous_status"], "authorized");
+        assert_eq!(second["cause"], "externalChange");
+        assert!(second["sequence"].as_u64() > first["sequence"].as_u64());
+    }
+
+    #[test]
+    fn test_ignores_unsubscribed_levels() {
+        let fixture = fixture();
+        fixture
+            .listener
+            .register_listener(PhotoKitAccessLevel::Read)
+            .unwrap();
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture.backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::AddOnly),
+            PermissionStatus::Denied,
+        );
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+        assert!(fixture.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detects_transitions_of_any_permission() {
+        let fixture = fixture();
+        fixture
+            .listener
+            .register_permission_listener(Permission::Accessibility)
+            .unwrap();
+        fixture
+            .backend
+            .set_status(Permission::Accessibility, PermissionStatus::Denied);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture
+            .backend
+            .set_status(Permission::Accessibility, PermissionStatus::Authorized);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 1);
+
+        let generic_events = fixture.generic_events.lock().unwrap();
+        assert_eq!(generic_events.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&generic_events[0]).unwrap();
+        assert_eq!(payload["permission"], "accessibility");
+        assert_eq!(payload["status"], "authorized");
+
+        // 非 PhotoKit 权限不发送旧的 PhotoKit 事件
+        assert!(fixture.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_feeds_rust_subscribers() {
+        let fixture = fixture();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = received.clone();
+        let _guard = fixture
+            .listener
+            .on_change(Permission::ScreenRecording, move |event| {
+                statuses.lock().unwrap().push(event.status);
+            })
+            .unwrap();
+        fixture
+            .backend
+            .set_status(Permission::ScreenRecording, PermissionStatus::Authorized);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture
+            .backend
+            .set_status(Permission::ScreenRecording, PermissionStatus::Denied);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 1);
+        assert_eq!(*received.lock().unwrap(), vec![PermissionStatus::Denied]);
+
+        // 回调订阅不会发送前端事件
+        assert!(fixture.generic_events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_debounce_coalesces_bursts() {
+        let fixture = fixture_with_debounce_window(DEBOUNCE_WINDOW);
+        let read = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+        fixture
+            .listener
+            .register_listener(PhotoKitAccessLevel::Read)
+            .unwrap();
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        // 用户在对话框中操作时状态连续变化
+        fixture.backend.set_status(read, PermissionStatus::Limited);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture
+            .backend
+            .set_status(read, PermissionStatus::Authorized);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        // 最后一次变化后不足一个防抖窗口
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+        assert!(fixture.generic_events.lock().unwrap().is_empty());
+
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 1);
+
+        let generic_events = fixture.generic_events.lock().unwrap();
+        assert_eq!(generic_events.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&generic_events[0]).unwrap();
+        assert_eq!(payload["previousStatus"], "notDetermined");
+        assert_eq!(payload["status"], "authorized");
+
+        let events = fixture.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&events[0]).unwrap();
+        assert_eq!(payload["previous_status"], "notDetermined");
+        assert_eq!(payload["new_status"], "authorized");
+    }
+
+    #[test]
+    fn test_debounce_drops_changes_that_revert() {
+        let fixture = fixture_with_debounce_window(DEBOUNCE_WINDOW);
+        fixture
+            .listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Authorized);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Denied);
+        fixture.clock.advance(INTERVAL);
+        assert_eq!(fixture.detector.tick().unwrap(), 0);
+
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Authorized);
+        for _ in 0..3 {
+            fixture.clock.advance(INTERVAL);
+            assert_eq!(fixture.detector.tick().unwrap(), 0);
+        }
+        assert!(fixture.generic_events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_backs_off_while_nothing_changes() {
+        let fixture = fixture();
+        let detector = ChangeDetector::new(
+            PhotoKitPermissionManager::with_backend(fixture.backend.clone(), None),
+            fixture.listener.clone(),
+            fixture.clock.clone(),
+            INTERVAL,
+        )
+        .with_max_interval(INTERVAL * 4);
+        fixture
+            .listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+        fixture
+            .listener
+            .register_permission_listener(Permission::Microphone)
+            .unwrap();
+
+        // 状态不变时间隔逐次翻倍，不超过最长间隔
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            if let Some(interval) = intervals.last() {
+                fixture.clock.advance(Duration::from_millis(*interval));
+            }
+            assert_eq!(detector.tick().unwrap(), 0);
+            let stats = fixture.listener.scheduler_stats().unwrap();
+            intervals.push(stats.current_interval_ms.unwrap());
+        }
+        assert_eq!(intervals, vec![2_000, 4_000, 8_000, 8_000]);
+
+        // 间隔未到期时不检查
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Authorized);
+        fixture.clock.advance(INTERVAL * 2);
+        assert_eq!(detector.tick().unwrap(), 0);
+        fixture.clock.advance(INTERVAL * 2);
+        assert_eq!(detector.tick().unwrap(), 1);
+
+        // 检测到变化后恢复基础间隔
+        let stats = fixture.listener.scheduler_stats().unwrap();
+        assert_eq!(stats.current_interval_ms, Some(2_000));
+        assert_eq!(stats.watched_permissions, 2);
+        assert_eq!(stats.polls, 5);
+        assert_eq!(stats.changes_detected, 1);
+    }
+
+    #[test]
+    fn test_poll_soon_resets_back_off() {
+        let fixture = fixture();
+        let detector = ChangeDetector::new(
+            PhotoKitPermissionManager::with_backend(fixture.backend.clone(), None),
+            fixture.listener.clone(),
+            fixture.clock.clone(),
+            INTERVAL,
+        )
+        .with_max_interval(INTERVAL * 8);
+        fixture
+            .listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+        for _ in 0..3 {
+            detector.tick().unwrap();
+            fixture.clock.advance(INTERVAL * 8);
+        }
+        let stats = fixture.listener.scheduler_stats().unwrap();
+        assert_eq!(stats.current_interval_ms, Some(8_000));
+
+        // 唤醒后立即检查，并从基础间隔重新开始
+        fixture.listener.poll_soon();
+        assert_eq!(
+            fixture.listener.scheduler_stats().unwrap().next_poll_at,
+            None
+        );
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Denied);
+        assert_eq!(detector.tick().unwrap(), 1);
+        assert_eq!(
+            fixture
+                .listener
+                .scheduler_stats()
+                .unwrap()
+                .current_interval_ms,
+            Some(2_000)
+        );
+    }
+
+    #[test]
+    fn test_probes_do_not_overlap() {
+        let fixture = fixture();
+        fixture
+            .listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+
+        let probe = fixture.listener.scheduler().lock_probe().unwrap();
+        std::thread::scope(|scope| {
+            let tick = scope.spawn(|| fixture.detector.tick());
+
+            // 另一次检查进行时，检测线程等待它结束
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(!tick.is_finished());
+
+            drop(probe);
+            assert!(tick.join().unwrap().is_ok());
+        });
+    }
+
+    #[test]
+    fn test_detector_released_when_idle() {
+        let fixture = fixture();
+        let guard = fixture
+            .listener
+            .on_change(Permission::Camera, |_| {})
+            .unwrap();
+        assert!(!fixture.listener.release_detector_if_idle());
+
+        drop(guard);
+        assert!(fixture.listener.release_detector_if_idle());
+        assert!(!fixture.listener.scheduler_stats().unwrap().running);
+    }
+
+    #[test]
+    fn test_pending_change_sent_when_window_ends_after_back_off() {
+        let fixture = fixture_with_debounce_window(Duration::from_millis(500));
+        let detector = ChangeDetector::new(
+            PhotoKitPermissionManager::with_backend(fixture.backend.clone(), None),
+            fixture.listener.clone(),
+            fixture.clock.clone(),
+            INTERVAL,
+        )
+        .with_max_interval(INTERVAL * 16);
+        fixture
+            .listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+
+        let mut interval = INTERVAL;
+        assert_eq!(detector.tick().unwrap(), 0);
+        for _ in 0..3 {
+            fixture.clock.advance(interval);
+            assert_eq!(detector.tick().unwrap(), 0);
+            interval *= 2;
+        }
+        assert_eq!(
+            fixture
+                .listener
+                .scheduler_stats()
+                .unwrap()
+                .current_interval_ms,
+            Some(16_000)
+        );
+
+        // 检测到的变化进入防抖窗口，下一次检查安排在窗口结束时
+        fixture
+            .backend
+            .set_status(Permission::Camera, PermissionStatus::Authorized);
+        fixture.clock.advance(interval);
+        assert_eq!(detector.tick().unwrap(), 0);
+        let stats = fixture.listener.scheduler_stats().unwrap();
+        assert_eq!(stats.current_interval_ms, Some(16_000));
+        assert_eq!(stats.next_poll_at, Some(fixture.clock.now_millis() + 500));
+
+        fixture.clock.advance(Duration::from_millis(499));
+        assert_eq!(detector.tick().unwrap(), 0);
+        fixture.clock.advance(Duration::from_millis(1));
+        assert_eq!(detector.tick().unwrap(), 1);
+
+        let generic_events = fixture.generic_events.lock().unwrap();
+        assert_eq!(generic_events.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&generic_events[0]).unwrap();
+        assert_eq!(payload["previousStatus"], "notDetermined");
+        assert_eq!(payload["status"], "authorized");
+    }
+
+    #[test]
+    fn test_debounce_skipped_without_polling() {
+        let fixture = fixture();
+        let listener =
+            PhotoKitPermissionListener::with_event_targets(fixture._app.handle().clone(), vec![])
+                .with_clock(fixture.clock.clone())
+                .with_debounce_window(DEBOUNCE_WINDOW);
+        listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+        listener
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::NotDetermined,
+                ChangeCause::ExternalChange,
+            )
+            .unwrap();
+
+        // 没有后台检测发送等待中的变化，请求结果立即分发
+        assert!(listener
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::Authorized,
+                ChangeCause::UserRequest,
+            )
+            .unwrap());
+        assert_eq!(listener.pending_deadline().unwrap(), None);
+        assert_eq!(fixture.generic_events.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+//! 时钟抽象
+//!
+//! 权限变化检测等依赖时间的逻辑通过 `Clock` 获取当前时间，
+//! 测试中可以注入 `ManualClock` 手动推进时间，而不必真正等待。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 在插件组件之间共享的时钟
+pub type SharedClock = Arc<dyn Clock>;
+
+/// 时钟
+pub trait Clock: Send + Sync {
+    /// 当前时间（Unix 时间戳，毫秒）
+    fn now_millis(&self) -> u64;
+}
+
+/// 使用系统时间的时钟
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+}
+
+/// 手动推进的时钟，用于测试
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    millis: AtomicU64,
+}
+
+impl ManualClock {
+    /// 创建从指定时间开始的时钟
+    ///
+    /// # Arguments
+    /// * `millis` - 初始时间（Unix 时间戳，毫秒）
+    pub fn new(millis: u64) -> Self {
+        Self {
+            millis: AtomicU64::new(millis),
+        }
+    }
+
+    /// 将时钟向前推进指定时长
+    pub fn advance(&self, duration: Duration) {
+        self.millis
+            .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_millis(&self) -> u64 {
+        self.millis.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_advance() {
+        let clock = ManualClock::new(1_000);
+        assert_eq!(clock.now_millis(), 1_000);
+
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(clock.now_millis(), 1_250);
+    }
+}
@@ -1,343 +1,325 @@
-use crate::{PhotoKitAccessLevel, PhotoKitAuthorizationStatus, PhotoKitPermissionManager};
-use tauri::{command, AppHandle, Runtime};
-
-#[cfg(target_os = "macos")]
-use crate::{ListenerInfo, PhotoKitPermissionListener};
-
-#[cfg(target_os = "macos")]
-use {
-    macos_accessibility_client::accessibility::{
-        application_is_trusted, application_is_trusted_with_prompt,
-    },
-    objc2::{class, msg_send, runtime::Bool},
-    objc2_foundation::NSString,
-    std::{fs::read_dir, process::Command},
-    tauri::Manager,
+use crate::{
+    ListenerInfo, ListenerTarget, MacosPermissionsExt, Permission, PermissionChangedEvent,
+    PermissionError, PermissionSnapshot, PermissionStatus, PhotoKitAccessLevel,
+    PhotoKitAuthorizationStatus, PhotoKitPermissionListener, PhotoKitPermissionManager,
+    SchedulerStats, TransitionFilter, PROBE_TIMEOUT,
 };
+use tauri::{command, ipc::Channel, AppHandle, Manager, Runtime, State, Webview};
 
-#[cfg(target_os = "macos")]
-#[link(name = "CoreGraphics", kind = "framework")]
-extern "C" {
-    fn CGPreflightScreenCaptureAccess() -> bool;
-    fn CGRequestScreenCaptureAccess() -> bool;
+/// 获取插件共享的 PhotoKit 权限管理器
+fn manager<R: Runtime>(app_handle: &AppHandle<R>) -> State<'_, PhotoKitPermissionManager> {
+    app_handle.state::<PhotoKitPermissionManager>()
 }
 
-#[cfg(target_os = "macos")]
-#[link(name = "IOKit", kind = "framework")]
-extern "C" {
-    fn IOHIDCheckAccess(request: u32) -> u32;
+/// 获取插件共享的权限监听器注册表
+fn listener<R: Runtime>(app_handle: &AppHandle<R>) -> State<'_, PhotoKitPermissionListener<R>> {
+    app_handle.state::<PhotoKitPermissionListener<R>>()
+}
+
+/// 注册监听器，未指定发送目标时使用配置的默认目标，未指定过滤条件时接收所有变化
+fn register<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    webview: &Webview<R>,
+    permission: Permission,
+    target: Option<ListenerTarget>,
+    filter: Option<TransitionFilter>,
+) -> Result<String, PermissionError> {
+    let listener = listener(app_handle);
+    let target = target.map_or_else(
+        || listener.default_target().clone(),
+        |target| target.resolve(webview.label()),
+    );
+
+    listener
+        .register_webview_listener(
+            permission,
+            target,
+            webview.label(),
+            filter.unwrap_or_default(),
+        )
+        .map_err(PermissionError::from)
 }
 
 /// Check accessibility permission.
 ///
 /// # Returns
-/// - `bool`: `true` if accessibility permission are granted, `false` otherwise.
+/// - `Ok(true)` if accessibility permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_accessibility_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_accessibility_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// let authorized = check_accessibility_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let authorized = check_accessibility_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_accessibility_permission() -> bool {
-    #[cfg(target_os = "macos")]
-    return application_is_trusted();
-
-    #[cfg(not(target_os = "macos"))]
-    return true;
+pub async fn check_accessibility_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::Accessibility)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request accessibility permission.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_accessibility_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_accessibility_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_accessibility_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// request_accessibility_permission(app_handle).await;
 /// # }
 /// ```
 #[command]
-pub async fn request_accessibility_permission() {
-    #[cfg(target_os = "macos")]
-    application_is_trusted_with_prompt();
+pub async fn request_accessibility_permission<R: Runtime>(app_handle: AppHandle<R>) {
+    let _ = request_permission(app_handle, Permission::Accessibility).await;
 }
 
 /// Check full disk access permission.
 ///
 /// # Returns
-/// - `bool`: `true` if full disk access permission are granted, `false` otherwise.
+/// - `Ok(true)` if full disk access permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_full_disk_access_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_full_disk_access_permission;
 /// use tauri::AppHandle;
 ///
 /// # async fn example(app_handle: AppHandle<tauri::Wry>) {
-/// let authorized = check_full_disk_access_permission(app_handle).await;
+/// let authorized = check_full_disk_access_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_full_disk_access_permission<R: Runtime>(app_handle: AppHandle<R>) -> bool {
-    #[cfg(target_os = "macos")]
-    {
-        // Reference: https://github.com/inket/FullDiskAccess/blob/846e04ea2b84fce843f47d7e7f3421189221829c/Sources/FullDiskAccess/FullDiskAccess.swift#L46
-        let check_dirs = vec!["Library/Containers/com.apple.stocks", "Library/Safari"];
-
-        if let Ok(home_dir) = app_handle.path().home_dir() {
-            for check_dir in check_dirs.iter() {
-                if read_dir(&home_dir.join(check_dir)).is_ok() {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    {
-        let _ = app_handle;
-
-        true
-    }
+pub async fn check_full_disk_access_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::FullDiskAccess)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request full disk access permission.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_full_disk_access_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_full_disk_access_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_full_disk_access_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// request_full_disk_access_permission(app_handle).await;
 /// # }
 /// ```
 #[command]
-pub async fn request_full_disk_access_permission() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles")
-            .output()
-            .map_err(|error| error.to_string())?;
-    }
-
-    Ok(())
+pub async fn request_full_disk_access_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<(), PermissionError> {
+    request_permission(app_handle, Permission::FullDiskAccess)
+        .await
+        .map(|_| ())
 }
 
 /// Check screen recording permission.
 ///
 /// # Returns
-/// - `bool`: `true` if screen recording permission are granted, `false` otherwise.
+/// - `Ok(true)` if screen recording permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_screen_recording_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_screen_recording_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// let authorized = check_screen_recording_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let authorized = check_screen_recording_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_screen_recording_permission() -> bool {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        CGPreflightScreenCaptureAccess()
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    return true;
+pub async fn check_screen_recording_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::ScreenRecording)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request screen recording permission.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_screen_recording_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_screen_recording_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_screen_recording_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// request_screen_recording_permission(app_handle).await;
 /// # }
 /// ```
 #[command]
-pub async fn request_screen_recording_permission() {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        CGRequestScreenCaptureAccess();
-    }
+pub async fn request_screen_recording_permission<R: Runtime>(app_handle: AppHandle<R>) {
+    let _ = request_permission(app_handle, Permission::ScreenRecording).await;
 }
 
 /// Check microphone permission.
 ///
 /// # Returns
-/// - `bool`: `true` if microphone permission is granted, `false` otherwise.
+/// - `Ok(true)` if microphone permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_microphone_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_microphone_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// let authorized = check_microphone_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let authorized = check_microphone_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_microphone_permission() -> bool {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        let av_media_type = NSString::from_str("soun");
-        let status: i32 = msg_send![
-            class!(AVCaptureDevice),
-            authorizationStatusForMediaType: &*av_media_type
-        ];
-
-        status == 3
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    return true;
+pub async fn check_microphone_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::Microphone)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request microphone permission.
 ///
+/// Resolves once the user has answered the system prompt.
+///
+/// # Returns
+/// - `Result<PermissionStatus, PermissionError>`: The authorization status chosen by the user, or a structured error
+///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_microphone_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_microphone_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_microphone_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let status = request_microphone_permission(app_handle).await;
+/// println!("Status: {:?}", status); // Ok(Authorized)
 /// # }
 /// ```
 #[command]
-pub async fn request_microphone_permission() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        let av_media_type = NSString::from_str("soun");
-        type CompletionBlock = Option<extern "C" fn(Bool)>;
-        let completion_block: CompletionBlock = None;
-        let _: () = msg_send![
-            class!(AVCaptureDevice),
-            requestAccessForMediaType: &*av_media_type,
-            completionHandler: completion_block
-        ];
-    }
-
-    Ok(())
+pub async fn request_microphone_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<PermissionStatus, PermissionError> {
+    request_permission(app_handle, Permission::Microphone).await
 }
 
 /// Check camera permission.
 ///
 /// # Returns
-/// - `bool`: `true` if camera permission is granted, `false` otherwise.
+/// - `Ok(true)` if camera permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_camera_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_camera_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// let authorized = check_camera_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let authorized = check_camera_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_camera_permission() -> bool {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        let av_media_type = NSString::from_str("vide");
-        let status: i32 = msg_send![
-            class!(AVCaptureDevice),
-            authorizationStatusForMediaType: &*av_media_type
-        ];
-
-        status == 3
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    return true;
+pub async fn check_camera_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::Camera)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request camera permission.
 ///
+/// Resolves once the user has answered the system prompt.
+///
+/// # Returns
+/// - `Result<PermissionStatus, PermissionError>`: The authorization status chosen by the user, or a structured error
+///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_camera_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_camera_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_camera_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let status = request_camera_permission(app_handle).await;
+/// println!("Status: {:?}", status); // Ok(Authorized)
 /// # }
 /// ```
 #[command]
-pub async fn request_camera_permission() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        let av_media_type = NSString::from_str("vide");
-        type CompletionBlock = Option<extern "C" fn(Bool)>;
-        let completion_block: CompletionBlock = None;
-        let _: () = msg_send![
-            class!(AVCaptureDevice),
-            requestAccessForMediaType: &*av_media_type,
-            completionHandler: completion_block
-        ];
-    }
-
-    Ok(())
+pub async fn request_camera_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<PermissionStatus, PermissionError> {
+    request_permission(app_handle, Permission::Camera).await
 }
 
 /// Check input monitoring permission.
 ///
 /// # Returns
-/// - `bool`: `true` if input monitoring permission is granted, `false` otherwise.
+/// - `Ok(true)` if input monitoring permission is granted, `Ok(false)` otherwise.
+///
+/// # Errors
+/// Returns a `PermissionError` if the permission is disabled or cannot be checked on this platform.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::check_input_monitoring_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::check_input_monitoring_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// let authorized = check_input_monitoring_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// let authorized = check_input_monitoring_permission(app_handle).await.unwrap();
 /// println!("Authorized: {}", authorized); // false
 /// # }
 /// ```
 #[command]
-pub async fn check_input_monitoring_permission() -> bool {
-    #[cfg(target_os = "macos")]
-    unsafe {
-        let status = IOHIDCheckAccess(1);
-
-        status == 0
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    return true;
+pub async fn check_input_monitoring_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<bool, PermissionError> {
+    check_permission(app_handle, Permission::InputMonitoring)
+        .await
+        .map(PermissionStatus::is_granted)
 }
 
 /// Request input monitoring permission.
 ///
 /// # Example
 /// ```no_run
-/// use tauri_plugin_macos_permissions::request_input_monitoring_permission;
+/// use tauri_plugin_macos_permissions_with_photokit::request_input_monitoring_permission;
+/// use tauri::AppHandle;
 ///
-/// # async fn example() {
-/// request_input_monitoring_permission().await;
+/// # async fn example(app_handle: AppHandle<tauri::Wry>) {
+/// request_input_monitoring_permission(app_handle).await;
 /// # }
 /// ```
 #[command]
-pub async fn request_input_monitoring_permission() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent")
-            .output()
-            .map_err(|error| error.to_string())?;
-    }
-
-    Ok(())
+pub async fn request_input_monitoring_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<(), PermissionError> {
+    request_permission(app_handle, Permission::InputMonitoring)
+        .await
+        .map(|_| ())
 }
 
 /// Check PhotoKit permission for the specified access level.
@@ -346,7 +328,7 @@ pub async fn request_input_monitoring_permission() -> Result<(), String> {
 /// * `access_level` - The PhotoKit access level to check
 ///
 /// # Returns
-/// - `PhotoKitAuthorizationStatus`: The current authorization status for the specified access level
+/// - `Result<PhotoKitAuthorizationStatus, PermissionError>`: The current authorization status for the specified access level, or a structured error
 ///
 /// # Example
 /// ```javascript
@@ -358,18 +340,13 @@ pub async fn request_input_monitoring_permission() -> Result<(), String> {
 /// console.log('权限状态:', status); // "authorized" | "denied" | "notDetermined" | ...
 /// ```
 #[command]
-pub async fn check_photokit_permission(
+pub async fn check_photokit_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
     access_level: PhotoKitAccessLevel,
-) -> PhotoKitAuthorizationStatus {
-    let manager = PhotoKitPermissionManager::new(None);
-
-    match manager.check_authorization_status(access_level) {
-        Ok(status) => status,
-        Err(_) => {
-            // 在错误情况下，返回未确定状态
-            PhotoKitAuthorizationStatus::NotDetermined
-        }
-    }
+) -> Result<PhotoKitAuthorizationStatus, PermissionError> {
+    manager(&app_handle)
+        .check_authorization_status(access_level)
+        .map_err(|e| PermissionError::from(e).with_permission(Permission::PhotoKit(access_level)))
 }
 
 /// Request PhotoKit permission for the specified access level.
@@ -380,7 +357,7 @@ pub async fn check_photokit_permission(
 /// * `access_level` - The PhotoKit access level to request
 ///
 /// # Returns
-/// - `Result<PhotoKitAuthorizationStatus, String>`: The authorization status after user response, or error message
+/// - `Result<PhotoKitAuthorizationStatus, PermissionError>`: The authorization status after user response, or a structured error
 ///
 /// # Example
 /// ```javascript
@@ -401,42 +378,57 @@ pub async fn check_photokit_permission(
 /// }
 /// ```
 #[command]
-pub async fn request_photokit_permission(
+pub async fn request_photokit_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
     access_level: PhotoKitAccessLevel,
-) -> Result<PhotoKitAuthorizationStatus, String> {
-    let manager = PhotoKitPermissionManager::new(None);
-
-    manager
-        .request_authorization(access_level)
-        .map_err(|e| e.to_string())
+) -> Result<PhotoKitAuthorizationStatus, PermissionError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle
+            .macos_permissions()
+            .request_permission(Permission::PhotoKit(access_level))
+            .map(Into::into)
+    })
+    .await?
 }
 
 /// Register a PhotoKit permission status listener.
 ///
 /// This creates a listener that will emit events when the PhotoKit permission status changes
-/// for the specified access level.
+/// for the specified access level. The listener belongs to the calling webview and is removed
+/// when that webview is destroyed or reloaded.
+///
+/// The current status is sent right away with `cause: "initial"`, so start listening for the
+/// event before registering. The `initial` event only goes to the calling webview, even when
+/// `target` names other windows or broadcasts, so other windows do not see a status they did not
+/// ask for. Later changes go to `target`. Changes that do not match `filter` are never sent to the
+/// listener's targets.
 ///
 /// # Arguments
 /// * `app_handle` - The Tauri application handle
+/// * `webview` - The calling webview, used when `target` is `"caller"`
 /// * `access_level` - The PhotoKit access level to monitor
+/// * `target` - Where to send change events: `"caller"`, `{ labels: [...] }` or `"broadcast"`.
+///   Defaults to the configured `eventTargets`
+/// * `filter` - Which changes to send: `"onGrant"`, `"onRevoke"`, `"onAnyChange"` or
+///   `{ statuses: [...] }`. Defaults to `"onAnyChange"`
 ///
 /// # Returns
-/// - `Result<String, String>`: The listener ID on success, or error message on failure
+/// - `Result<String, PermissionError>`: The listener ID on success, or a structured error on failure
 ///
 /// # Example
 /// ```javascript
 /// import { invoke, listen } from '@tauri-apps/api';
 ///
+/// // 监听权限状态变化事件，注册时会先收到当前状态
+/// const unlisten = await listen('photokit-permission-changed', (event) => {
+///     console.log('权限状态变化:', event.payload);
+/// });
+///
 /// // 注册监听器
 /// const listenerId = await invoke('register_photokit_permission_listener', {
 ///     accessLevel: 'read'
 /// });
 ///
-/// // 监听权限状态变化事件
-/// const unlisten = await listen('photokit-permission-changed', (event) => {
-///     console.log('权限状态变化:', event.payload);
-/// });
-///
 /// // 稍后注销监听器
 /// await invoke('unregister_photokit_permission_listener', {
 ///     listenerId: listenerId
@@ -445,22 +437,18 @@ pub async fn request_photokit_permission(
 #[command]
 pub async fn register_photokit_permission_listener<R: Runtime>(
     app_handle: AppHandle<R>,
+    webview: Webview<R>,
     access_level: PhotoKitAccessLevel,
-) -> Result<String, String> {
-    #[cfg(target_os = "macos")]
-    {
-        let listener = PhotoKitPermissionListener::new(app_handle);
-        listener
-            .register_listener(access_level)
-            .map_err(|e| e.to_string())
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    {
-        // 在非 macOS 平台上，返回一个模拟的监听器 ID
-        use uuid::Uuid;
-        Ok(Uuid::new_v4().to_string())
-    }
+    target: Option<ListenerTarget>,
+    filter: Option<TransitionFilter>,
+) -> Result<String, PermissionError> {
+    register(
+        &app_handle,
+        &webview,
+        Permission::PhotoKit(access_level),
+        target,
+        filter,
+    )
 }
 
 /// Unregister a PhotoKit permission status listener.
@@ -472,7 +460,7 @@ pub async fn register_photokit_permission_listener<R: Runtime>(
 /// * `listener_id` - The ID of the listener to unregister
 ///
 /// # Returns
-/// - `Result<(), String>`: Success or error message
+/// - `Result<(), PermissionError>`: Success or a structured error
 ///
 /// # Example
 /// ```javascript
@@ -492,31 +480,24 @@ pub async fn register_photokit_permission_listener<R: Runtime>(
 pub async fn unregister_photokit_permission_listener<R: Runtime>(
     app_handle: AppHandle<R>,
     listener_id: String,
-) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        let listener = PhotoKitPermissionListener::new(app_handle);
-        listener
-            .unregister_listener(&listener_id)
-            .map_err(|e| e.to_string())
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    {
-        // 在非 macOS 平台上，这是一个空操作
-        Ok(())
-    }
+) -> Result<(), PermissionError> {
+    listener(&app_handle)
+        .unregister_listener(&listener_id)
+        .map_err(PermissionError::from)
 }
 
-/// Get all active PhotoKit permission listeners.
+/// Get all PhotoKit permission listeners.
 ///
-/// This returns information about all currently registered permission listeners.
+/// This returns information about all currently registered PhotoKit permission listeners.
+/// Paused listeners are included with `active` set to `false`, and `owner` is the label of
+/// the webview that registered the listener.
+/// Use `get_permission_listeners` to include listeners for other permissions.
 ///
 /// # Arguments
 /// * `app_handle` - The Tauri application handle
 ///
 /// # Returns
-/// - `Result<Vec<ListenerInfo>, String>`: List of active listeners or error message
+/// - `Result<Vec<ListenerInfo>, PermissionError>`: List of listeners or a structured error
 ///
 /// # Example
 /// ```javascript
@@ -528,18 +509,255 @@ pub async fn unregister_photokit_permission_listener<R: Runtime>(
 #[command]
 pub async fn get_photokit_permission_listeners<R: Runtime>(
     app_handle: AppHandle<R>,
-) -> Result<Vec<ListenerInfo>, String> {
-    #[cfg(target_os = "macos")]
-    {
-        let listener = PhotoKitPermissionListener::new(app_handle);
-        listener.get_active_listeners().map_err(|e| e.to_string())
-    }
+) -> Result<Vec<ListenerInfo>, PermissionError> {
+    let listeners = listener(&app_handle)
+        .get_all_listeners()
+        .map_err(PermissionError::from)?;
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        // 在非 macOS 平台上，返回空列表
-        Ok(vec![])
-    }
+    Ok(listeners
+        .into_iter()
+        .filter(|info| info.access_level().is_some())
+        .collect())
+}
+
+/// Register a permission status listener for any permission.
+///
+/// While the listener is registered, the plugin emits a `permission-changed` event whenever
+/// the status of the given permission changes. PhotoKit permissions additionally emit
+/// `photokit-permission-changed`. The listener belongs to the calling webview and is removed
+/// when that webview is destroyed or reloaded.
+///
+/// The current status is sent right away with `cause: "initial"`, so start listening for the
+/// event before registering. The `initial` event only goes to the calling webview, even when
+/// `target` names other windows or broadcasts, so other windows do not see a status they did not
+/// ask for. Later changes go to `target`. Changes that do not match `filter` are never sent to the
+/// listener's targets.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `webview` - The calling webview, used when `target` is `"caller"`
+/// * `permission` - The permission to monitor
+/// * `target` - Where to send change events: `"caller"`, `{ labels: [...] }` or `"broadcast"`.
+///   Defaults to the configured `eventTargets`
+/// * `filter` - Which changes to send: `"onGrant"`, `"onRevoke"`, `"onAnyChange"` or
+///   `{ statuses: [...] }`. Defaults to `"onAnyChange"`
+///
+/// # Returns
+/// - `Result<String, PermissionError>`: The listener ID on success, or a structured error on failure
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+/// import { listen } from '@tauri-apps/api/event';
+///
+/// const unlisten = await listen('permission-changed', (event) => {
+///     console.log('权限状态变化:', event.payload.permission, event.payload.status);
+/// });
+///
+/// const listenerId = await invoke('plugin:macos-permissions-with-photokit|register_permission_listener', {
+///     permission: 'accessibility',
+///     target: 'caller'
+/// });
+/// ```
+#[command]
+pub async fn register_permission_listener<R: Runtime>(
+    app_handle: AppHandle<R>,
+    webview: Webview<R>,
+    permission: Permission,
+    target: Option<ListenerTarget>,
+    filter: Option<TransitionFilter>,
+) -> Result<String, PermissionError> {
+    register(&app_handle, &webview, permission, target, filter)
+}
+
+/// Unregister a permission status listener.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `listener_id` - The ID returned by `register_permission_listener`
+///
+/// # Returns
+/// - `Result<(), PermissionError>`: Success or a structured error
+#[command]
+pub async fn unregister_permission_listener<R: Runtime>(
+    app_handle: AppHandle<R>,
+    listener_id: String,
+) -> Result<(), PermissionError> {
+    listener(&app_handle)
+        .unregister_listener(&listener_id)
+        .map_err(PermissionError::from)
+}
+
+/// Stream status changes of a permission to the caller's channel.
+///
+/// The channel first receives the current status with `cause: "initial"`.
+/// Unlike `register_permission_listener`, the subscription is tied to the channel: it ends
+/// automatically when sending to the channel fails or when the calling webview is destroyed or reloaded.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `webview` - The calling webview
+/// * `permission` - The permission to watch
+/// * `channel` - The channel that receives `PermissionChangedEvent`s
+/// * `filter` - Which changes to send: `"onGrant"`, `"onRevoke"`, `"onAnyChange"` or
+///   `{ statuses: [...] }`. Defaults to `"onAnyChange"`
+///
+/// # Returns
+/// - `Result<String, PermissionError>`: The listener ID, which can be passed to
+///   `unregister_permission_listener` to stop watching early
+///
+/// # Example
+/// ```javascript
+/// import { Channel, invoke } from '@tauri-apps/api/core';
+///
+/// const onChange = new Channel();
+/// onChange.onmessage = (event) => console.log('权限状态变化:', event.status);
+/// await invoke('plugin:macos-permissions-with-photokit|watch_permission', {
+///     permission: 'screenRecording',
+///     channel: onChange
+/// });
+/// ```
+#[command]
+pub async fn watch_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+    webview: Webview<R>,
+    permission: Permission,
+    channel: Channel<PermissionChangedEvent>,
+    filter: Option<TransitionFilter>,
+) -> Result<String, PermissionError> {
+    listener(&app_handle)
+        .watch_permission(
+            permission,
+            channel,
+            webview.label().to_string(),
+            filter.unwrap_or_default(),
+        )
+        .map_err(PermissionError::from)
+}
+
+/// Get all permission listeners, including PhotoKit listeners.
+///
+/// Paused listeners are included with `active` set to `false`, and `owner` is the label of
+/// the webview that registered the listener.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+///
+/// # Returns
+/// - `Result<Vec<ListenerInfo>, PermissionError>`: List of listeners or a structured error
+#[command]
+pub async fn get_permission_listeners<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<ListenerInfo>, PermissionError> {
+    listener(&app_handle)
+        .get_all_listeners()
+        .map_err(PermissionError::from)
+}
+
+/// Pause a permission listener.
+///
+/// A paused listener stays registered but receives no change events until it is resumed.
+/// Changes that happen while it is paused are not replayed.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `listener_id` - The ID of the listener to pause
+///
+/// # Returns
+/// - `Result<(), PermissionError>`: Success or a structured error
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:macos-permissions-with-photokit|pause_permission_listener', {
+///     listenerId: listenerId
+/// });
+/// ```
+#[command]
+pub async fn pause_permission_listener<R: Runtime>(
+    app_handle: AppHandle<R>,
+    listener_id: String,
+) -> Result<(), PermissionError> {
+    listener(&app_handle)
+        .pause_listener(&listener_id)
+        .map_err(PermissionError::from)
+}
+
+/// Resume a paused permission listener.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `listener_id` - The ID of the listener to resume
+///
+/// # Returns
+/// - `Result<(), PermissionError>`: Success or a structured error
+#[command]
+pub async fn resume_permission_listener<R: Runtime>(
+    app_handle: AppHandle<R>,
+    listener_id: String,
+) -> Result<(), PermissionError> {
+    listener(&app_handle)
+        .resume_listener(&listener_id)
+        .map_err(PermissionError::from)
+}
+
+/// Get the permission change events of the current session.
+///
+/// The plugin keeps the most recent `historyCapacity` change events in memory, oldest first.
+/// The `initial` events sent to newly registered listeners are not recorded.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `since` - Only return events whose timestamp (Unix milliseconds) is at or after this time
+/// * `permission` - Only return events of this permission, or of all permissions when omitted
+///
+/// # Returns
+/// - `Result<Vec<PermissionChangedEvent>, PermissionError>`: The matching events, oldest first
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const history = await invoke('plugin:macos-permissions-with-photokit|get_permission_event_history', {
+///     since: 0,
+///     permission: 'screenRecording'
+/// });
+/// ```
+#[command]
+pub async fn get_permission_event_history<R: Runtime>(
+    app_handle: AppHandle<R>,
+    since: u64,
+    permission: Option<Permission>,
+) -> Result<Vec<PermissionChangedEvent>, PermissionError> {
+    listener(&app_handle)
+        .event_history(since, permission)
+        .map_err(PermissionError::from)
+}
+
+/// Get statistics of the scheduler that polls watched permissions for changes.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+///
+/// # Returns
+/// - `Result<SchedulerStats, PermissionError>`: Whether the scheduler runs, its current
+///   interval, poll and change counts, or a structured error
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const stats = await invoke('plugin:macos-permissions-with-photokit|get_scheduler_stats');
+/// console.log(stats.currentIntervalMs, stats.polls);
+/// ```
+#[command]
+pub async fn get_scheduler_stats<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<SchedulerStats, PermissionError> {
+    listener(&app_handle)
+        .scheduler_stats()
+        .map_err(PermissionError::from)
 }
 
 /// Get the total number of photos in the photo library.
@@ -548,7 +766,7 @@ pub async fn get_photokit_permission_listeners<R: Runtime>(
 /// The user must have granted read permission for this to work.
 ///
 /// # Returns
-/// - `Result<u64, String>`: The total number of photos, or error message
+/// - `Result<u64, PermissionError>`: The total number of photos, or a structured error
 ///
 /// # Example
 /// ```javascript
@@ -562,75 +780,158 @@ pub async fn get_photokit_permission_listeners<R: Runtime>(
 /// }
 /// ```
 #[command]
-pub async fn get_photos_count() -> Result<u64, String> {
-    let manager = PhotoKitPermissionManager::new(None);
+pub async fn get_photos_count<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<u64, PermissionError> {
+    manager(&app_handle)
+        .get_photos_count()
+        .map_err(PermissionError::from)
+}
+
+/// Check the authorization status of the given permission.
+///
+/// Every permission kind is dispatched through this single command, so a data-driven
+/// permissions screen only needs one code path. Unlike the boolean `check_*_permission`
+/// commands, the result tells "never asked" (`notDetermined`) apart from `denied`.
+///
+/// Accessibility, full disk access and screen recording can only be reported as
+/// `authorized` or `denied`, because macOS does not expose more detail for them.
+///
+/// # Arguments
+/// * `permission` - The permission to check, e.g. `"camera"` or `{ "photoKit": "read" }`
+///
+/// # Returns
+/// - `Result<PermissionStatus, PermissionError>`: The current authorization status, or a structured error
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const status = await invoke('plugin:macos-permissions-with-photokit|check_permission', {
+///     permission: { photoKit: 'read' }
+/// });
+/// console.log(status); // "notDetermined" | "restricted" | "denied" | "authorized" | "limited"
+/// ```
+#[command]
+pub async fn check_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+    permission: Permission,
+) -> Result<PermissionStatus, PermissionError> {
+    manager(&app_handle)
+        .check_permission(permission)
+        .map_err(|e| PermissionError::from(e).with_permission(permission))
+}
+
+/// Request the given permission.
+///
+/// # Arguments
+/// * `permission` - The permission to request, e.g. `"screenRecording"` or `{ "photoKit": "readWrite" }`
+///
+/// # Returns
+/// - `Result<PermissionStatus, PermissionError>`: The authorization status after the request, or a structured error
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const status = await invoke('plugin:macos-permissions-with-photokit|request_permission', {
+///     permission: 'camera'
+/// });
+/// ```
+#[command]
+pub async fn request_permission<R: Runtime>(
+    app_handle: AppHandle<R>,
+    permission: Permission,
+) -> Result<PermissionStatus, PermissionError> {
+    // 请求会阻塞到用户响应系统对话框，放到阻塞线程池中执行
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle
+            .macos_permissions()
+            .request_permission(permission)
+    })
+    .await?
+}
 
-    manager.get_photos_count().map_err(|e| e.to_string())
+/// Check every enabled permission at once.
+///
+/// The checks run concurrently. A check that takes longer than `PROBE_TIMEOUT` is reported in
+/// `timedOut` instead of blocking the whole snapshot, and a check that fails is reported in `failed`.
+///
+/// # Returns
+/// - `Result<PermissionSnapshot, PermissionError>`: The status of every enabled permission with its capture time, or a structured error
+///
+/// # Example
+/// ```javascript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const snapshot = await invoke('plugin:macos-permissions-with-photokit|check_all_permissions');
+/// for (const { permission, status } of snapshot.statuses) {
+///     console.log(permission, status);
+/// }
+/// ```
+#[command]
+pub async fn check_all_permissions<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<PermissionSnapshot, PermissionError> {
+    let manager = manager(&app_handle).inner().clone();
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || manager.check_all_permissions(PROBE_TIMEOUT))
+            .await?,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{init_with_backend, EmitTarget, FakePermissionBackend};
+    use std::sync::Arc;
+    use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
 
-    #[tokio::test]
-    #[cfg(target_os = "macos")]
-    async fn test_check_photokit_permission_macos() {
-        // 测试检查权限功能（仅在 macOS 上运行）
-        // 注意：在某些测试环境中可能没有完整的PhotoKit框架
-        let status = check_photokit_permission(PhotoKitAccessLevel::Read).await;
-
-        // 在 macOS 平台上应该返回有效的状态
-        assert!(matches!(
-            status,
-            PhotoKitAuthorizationStatus::NotDetermined
-                | PhotoKitAuthorizationStatus::Restricted
-                | PhotoKitAuthorizationStatus::Denied
-                | PhotoKitAuthorizationStatus::Authorized
-                | PhotoKitAuthorizationStatus::Limited
-        ));
+    /// 在测试应用中创建指定标签的 webview
+    fn mock_webview(app: &tauri::App<MockRuntime>, label: &str) -> Webview<MockRuntime> {
+        tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::default())
+            .build()
+            .expect("failed to build mock webview")
+            .as_ref()
+            .clone()
     }
 
-    #[tokio::test]
-    #[cfg(not(target_os = "macos"))]
-    async fn test_check_photokit_permission_non_macos() {
-        // 测试检查权限功能（在非 macOS 平台上运行）
-        let status = check_photokit_permission(PhotoKitAccessLevel::Read).await;
-
-        // 在非 macOS 平台上应该返回 NotDetermined
-        assert_eq!(status, PhotoKitAuthorizationStatus::NotDetermined);
+    /// 使用模拟后端构建测试应用
+    fn mock_app(backend: Arc<FakePermissionBackend>) -> tauri::App<MockRuntime> {
+        mock_builder()
+            .plugin(init_with_backend(backend))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app")
     }
 
     #[tokio::test]
-    #[cfg(target_os = "macos")]
-    async fn test_request_photokit_permission_macos() {
-        // 测试请求权限功能（仅在 macOS 上运行）
-        let result = request_photokit_permission(PhotoKitAccessLevel::Read).await;
-
-        // 应该返回成功结果
-        assert!(result.is_ok());
-
-        let status = result.unwrap();
-        assert!(matches!(
-            status,
-            PhotoKitAuthorizationStatus::NotDetermined
-                | PhotoKitAuthorizationStatus::Restricted
-                | PhotoKitAuthorizationStatus::Denied
-                | PhotoKitAuthorizationStatus::Authorized
-                | PhotoKitAuthorizationStatus::Limited
-        ));
+    async fn test_check_photokit_permission() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Limited,
+        );
+        let app = mock_app(backend);
+
+        let status =
+            check_photokit_permission(app.handle().clone(), PhotoKitAccessLevel::Read).await;
+        assert_eq!(status, Ok(PhotoKitAuthorizationStatus::Limited));
     }
 
     #[tokio::test]
-    #[cfg(not(target_os = "macos"))]
-    async fn test_request_photokit_permission_non_macos() {
-        // 测试请求权限功能（在非 macOS 平台上运行）
-        let result = request_photokit_permission(PhotoKitAccessLevel::Read).await;
+    async fn test_request_photokit_permission() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_app(backend.clone());
 
-        // 应该返回成功结果
-        assert!(result.is_ok());
+        let result =
+            request_photokit_permission(app.handle().clone(), PhotoKitAccessLevel::ReadWrite).await;
+        assert_eq!(result, Ok(PhotoKitAuthorizationStatus::Authorized));
+        assert_eq!(backend.request_count(), 1);
 
-        let status = result.unwrap();
-        assert_eq!(status, PhotoKitAuthorizationStatus::NotDetermined);
+        backend.set_grant_on_request(false);
+        let result =
+            request_photokit_permission(app.handle().clone(), PhotoKitAccessLevel::AddOnly).await;
+        assert_eq!(result, Ok(PhotoKitAuthorizationStatus::Denied));
     }
 
     #[test]
@@ -658,30 +959,367 @@ mod tests {
     #[tokio::test]
     async fn test_traditional_permissions() {
         // 测试传统权限检查功能
-        let accessibility = check_accessibility_permission().await;
-        let screen_recording = check_screen_recording_permission().await;
-        let microphone = check_microphone_permission().await;
-        let camera = check_camera_permission().await;
-        let input_monitoring = check_input_monitoring_permission().await;
-
-        // 在非 macOS 平台上应该都返回 true
-        #[cfg(not(target_os = "macos"))]
-        {
-            assert!(accessibility);
-            assert!(screen_recording);
-            assert!(microphone);
-            assert!(camera);
-            assert!(input_monitoring);
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(Permission::Accessibility, PermissionStatus::Authorized);
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        let app = mock_app(backend.clone());
+        let handle = app.handle().clone();
+
+        assert_eq!(
+            check_accessibility_permission(handle.clone()).await,
+            Ok(true)
+        );
+        assert_eq!(
+            check_full_disk_access_permission(handle.clone()).await,
+            Ok(false)
+        );
+        assert_eq!(
+            check_screen_recording_permission(handle.clone()).await,
+            Ok(false)
+        );
+        assert_eq!(check_microphone_permission(handle.clone()).await, Ok(false));
+        assert_eq!(check_camera_permission(handle.clone()).await, Ok(true));
+        assert_eq!(
+            check_input_monitoring_permission(handle.clone()).await,
+            Ok(false)
+        );
+
+        // 请求后状态应由后端更新
+        request_screen_recording_permission(handle.clone()).await;
+        assert_eq!(
+            request_microphone_permission(handle.clone()).await,
+            Ok(PermissionStatus::Authorized)
+        );
+        assert_eq!(
+            check_screen_recording_permission(handle.clone()).await,
+            Ok(true)
+        );
+        assert_eq!(check_microphone_permission(handle).await, Ok(true));
+        assert_eq!(backend.request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_photokit_state_is_shared_between_commands() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_app(backend.clone());
+        let handle = app.handle().clone();
+
+        // 管理器缓存在命令调用之间保持有效
+        let status = request_photokit_permission(handle.clone(), PhotoKitAccessLevel::Read).await;
+        assert_eq!(status, Ok(PhotoKitAuthorizationStatus::Authorized));
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Denied,
+        );
+        assert_eq!(
+            check_photokit_permission(handle.clone(), PhotoKitAccessLevel::Read).await,
+            Ok(PhotoKitAuthorizationStatus::Authorized)
+        );
+
+        // 监听器注册表在命令调用之间保持有效
+        let listener_id = register_photokit_permission_listener(
+            handle.clone(),
+            mock_webview(&app, "main"),
+            PhotoKitAccessLevel::Read,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let listeners = get_photokit_permission_listeners(handle.clone())
+            .await
+            .unwrap();
+        assert_eq!(listeners.len(), 1);
+        assert_eq!(listeners[0].id, listener_id);
+
+        assert!(
+            unregister_photokit_permission_listener(handle.clone(), listener_id)
+                .await
+                .is_ok()
+        );
+        assert!(get_photokit_permission_listeners(handle)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generic_permission_commands() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(Permission::FullDiskAccess, PermissionStatus::Authorized);
+        let app = mock_app(backend.clone());
+        let handle = app.handle().clone();
+
+        assert_eq!(
+            check_permission(handle.clone(), Permission::FullDiskAccess).await,
+            Ok(PermissionStatus::Authorized)
+        );
+        assert_eq!(
+            check_permission(handle.clone(), Permission::ScreenRecording).await,
+            Ok(PermissionStatus::NotDetermined)
+        );
+
+        let permission = Permission::PhotoKit(PhotoKitAccessLevel::ReadWrite);
+        assert_eq!(
+            request_permission(handle.clone(), permission).await,
+            Ok(PermissionStatus::Authorized)
+        );
+        assert_eq!(
+            check_permission(handle, permission).await,
+            Ok(PermissionStatus::Authorized)
+        );
+        assert_eq!(backend.request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_commands_resolve_with_user_choice() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_grant_on_request(false);
+        let app = mock_app(backend.clone());
+        let handle = app.handle().clone();
+
+        // 用户拒绝时，请求命令应返回拒绝后的状态，而不是请求前的 NotDetermined
+        assert_eq!(
+            request_camera_permission(handle.clone()).await,
+            Ok(PermissionStatus::Denied)
+        );
+        assert_eq!(
+            request_photokit_permission(handle.clone(), PhotoKitAccessLevel::Read).await,
+            Ok(PhotoKitAuthorizationStatus::Denied)
+        );
+
+        backend.set_grant_on_request(true);
+        assert_eq!(
+            request_permission(handle, Permission::Microphone).await,
+            Ok(PermissionStatus::Authorized)
+        );
+        assert_eq!(backend.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_commands_return_structured_errors() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_usage_description(Permission::Microphone, false);
+        let app = mock_app(backend.clone());
+        let handle = app.handle().clone();
+
+        let error = request_microphone_permission(handle.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "missingUsageDescription",
+                "permission": "microphone",
+                "key": "NSMicrophoneUsageDescription",
+            })
+        );
+        assert_eq!(backend.request_count(), 0);
+
+        let error = get_photos_count(handle.clone()).await.unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["code"],
+            "requestFailed"
+        );
+
+        let error = unregister_photokit_permission_listener(handle, "missing".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, PermissionError::RequestFailed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_check_all_permissions() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Limited,
+        );
+        let app = mock_app(backend);
+
+        let snapshot = check_all_permissions(app.handle().clone()).await.unwrap();
+        assert_eq!(snapshot.statuses.len(), Permission::ALL.len());
+        assert_eq!(
+            snapshot.get(Permission::Camera),
+            Some(PermissionStatus::Authorized)
+        );
+        assert_eq!(
+            snapshot.get(Permission::PhotoKit(PhotoKitAccessLevel::Read)),
+            Some(PermissionStatus::Limited)
+        );
+        assert_eq!(
+            snapshot.get(Permission::Microphone),
+            Some(PermissionStatus::NotDetermined)
+        );
+        assert!(snapshot.timed_out.is_empty());
+        assert!(snapshot.captured_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_photos_count() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_photos_count(7);
+        let app = mock_app(backend.clone());
+
+        assert!(get_photos_count(app.handle().clone()).await.is_err());
+
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Authorized,
+        );
+        assert_eq!(get_photos_count(app.handle().clone()).await, Ok(7));
+    }
+
+    #[tokio::test]
+    async fn test_permission_listeners() {
+        let app = mock_app(Arc::new(FakePermissionBackend::new()));
+        let webview = mock_webview(&app, "settings");
+
+        let photokit_id = register_photokit_permission_listener(
+            app.handle().clone(),
+            webview.clone(),
+            PhotoKitAccessLevel::Read,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let accessibility_id = register_permission_listener(
+            app.handle().clone(),
+            webview,
+            Permission::Accessibility,
+            Some(ListenerTarget::Caller),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_permission_listeners(app.handle().clone())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let photokit_listeners = get_photokit_permission_listeners(app.handle().clone())
+            .await
+            .unwrap();
+        assert_eq!(photokit_listeners.len(), 1);
+        assert_eq!(photokit_listeners[0].id, photokit_id);
+        assert_eq!(photokit_listeners[0].owner.as_deref(), Some("settings"));
+        assert_eq!(
+            photokit_listeners[0].target,
+            EmitTarget::Labels(vec!["main".to_string()])
+        );
+        assert!(get_permission_listeners(app.handle().clone())
+            .await
+            .unwrap()
+            .iter()
+            .any(|info| info.id == accessibility_id
+                && info.target == EmitTarget::Webview("settings".to_string())));
+
+        // 暂停的监听器仍然列出，并标记为不活跃
+        pause_permission_listener(app.handle().clone(), photokit_id.clone())
+            .await
+            .unwrap();
+        let photokit_listeners = get_photokit_permission_listeners(app.handle().clone())
+            .await
+            .unwrap();
+        assert_eq!(photokit_listeners.len(), 1);
+        assert!(!photokit_listeners[0].active);
+        resume_permission_listener(app.handle().clone(), photokit_id)
+            .await
+            .unwrap();
+        assert!(
+            get_photokit_permission_listeners(app.handle().clone())
+                .await
+                .unwrap()[0]
+                .active
+        );
+
+        unregister_permission_listener(app.handle().clone(), accessibility_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_permission_listeners(app.handle().clone())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_listeners_receive_current_status() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Limited,
+        );
+        let app = mock_app(backend);
+        let webview = mock_webview(&app, "main");
+
+        let messages = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received = messages.clone();
+        let channel = Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                received.lock().unwrap().push(json);
+            }
+            Ok(())
+        });
+
+        watch_permission(
+            app.handle().clone(),
+            webview,
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            channel,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&messages[0]).unwrap();
+        assert_eq!(payload["status"], "limited");
+        assert_eq!(payload["previousStatus"], serde_json::Value::Null);
+        assert_eq!(payload["cause"], "initial");
+    }
+
+    #[tokio::test]
+    async fn test_initial_status_only_sent_to_registering_webview() {
+        use tauri::Listener;
+
+        let app = mock_app(Arc::new(FakePermissionBackend::new()));
+        let main = mock_webview(&app, "main");
+        let settings = mock_webview(&app, "settings");
+
+        let mut counters = Vec::new();
+        for webview in [&main, &settings] {
+            let count = Arc::new(std::sync::Mutex::new(0));
+            let received = count.clone();
+            webview.listen("permission-changed", move |_| {
+                *received.lock().unwrap() += 1;
+            });
+            counters.push(count);
         }
 
-        // 在 macOS 平台上应该返回布尔值
-        #[cfg(target_os = "macos")]
-        {
-            assert!(accessibility == true || accessibility == false);
-            assert!(screen_recording == true || screen_recording == false);
-            assert!(microphone == true || microphone == false);
-            assert!(camera == true || camera == false);
-            assert!(input_monitoring == true || input_monitoring == false);
+        // 两个监听器都使用默认目标 main
+        for webview in [main, settings] {
+            register_permission_listener(
+                app.handle().clone(),
+                webview,
+                Permission::Camera,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
         }
+
+        // main 上已有的监听器不会再收到 settings 注册时补发的状态
+        assert_eq!(*counters[0].lock().unwrap(), 1);
+        assert_eq!(*counters[1].lock().unwrap(), 1);
     }
 }
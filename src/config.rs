@@ -0,0 +1,252 @@
+//! 插件配置
+//!
+//! `Config` 对应 `tauri.conf.json` 中的 `plugins.macos-permissions-with-photokit` 配置段，
+//! 所有字段都是可选的，缺省时使用与旧版本相同的默认值：
+//!
+//! ```json
+//! {
+//!   "plugins": {
+//!     "macos-permissions-with-photokit": {
+//!       "cacheTtl": 30,
+//!       "eventTargets": ["main"],
+//!       "pollingInterval": 2000,
+//!       "maxPollingInterval": 30000,
+//!       "debounceWindow": 0,
+//!       "historyCapacity": 100,
+//!       "persistSnapshot": true,
+//!       "fallbackPolicy": "assumeGranted",
+//!       "enabledPermissions": null
+//!     }
+//!   }
+//! }
+//! ```
+
+use crate::{Permission, PermissionStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 插件配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// PhotoKit 授权状态缓存的过期时间（秒）
+    #[serde(rename = "cacheTtl")]
+    pub cache_ttl: u64,
+
+    /// 注册时未指定发送目标的监听器使用的窗口标签，为空时广播到所有窗口
+    #[serde(rename = "eventTargets")]
+    pub event_targets: Vec<String>,
+
+    /// 检测权限变化的轮询间隔（毫秒），为 null 时不检测权限变化
+    #[serde(rename = "pollingInterval")]
+    pub polling_interval: Option<u64>,
+
+    /// 状态没有变化时轮询间隔逐次翻倍的上限（毫秒），为 null 时以固定间隔轮询
+    #[serde(rename = "maxPollingInterval")]
+    pub max_polling_interval: Option<u64>,
+
+    /// 权限状态稳定多久（毫秒）后才发送变化事件，为 0 时立即发送
+    ///
+    /// 窗口内的连续变化合并为一个事件，保留第一次变化之前的状态。
+    #[serde(rename = "debounceWindow")]
+    pub debounce_window: u64,
+
+    /// 最多保留的权限变化历史事件数量，为 0 时不记录
+    #[serde(rename = "historyCapacity")]
+    pub history_capacity: usize,
+
+    /// 是否在应用数据目录中保存权限快照，并在启动时发送与上次运行相比的变化
+    ///
+    /// 默认开启，插件会在应用数据目录中写入一个 `macos-permissions-snapshot.json` 文件。
+    /// 不希望写入文件时设置为 false。
+    #[serde(rename = "persistSnapshot")]
+    pub persist_snapshot: bool,
+
+    /// 非 macOS 平台上的权限回退策略
+    #[serde(rename = "fallbackPolicy")]
+    pub fallback_policy: FallbackPolicy,
+
+    /// 启用的权限类型，为 None 时启用全部权限
+    #[serde(rename = "enabledPermissions")]
+    pub enabled_permissions: Option<Vec<PermissionKind>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cache_ttl: 30,
+            event_targets: vec!["main".to_string()],
+            polling_interval: Some(2000),
+            max_polling_interval: Some(30_000),
+            debounce_window: 0,
+            history_capacity: 100,
+            persist_snapshot: true,
+            fallback_policy: FallbackPolicy::default(),
+            enabled_permissions: None,
+        }
+    }
+}
+
+/// 非 macOS 平台上的权限回退策略
+///
+/// 在没有系统权限 API 的平台上，插件无法得知真实的授权状态，由此策略决定命令的返回值。
+/// 在 macOS 上此策略不起作用。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FallbackPolicy {
+    /// 所有权限都视为已授予（与旧版本行为一致）
+    #[default]
+    #[serde(rename = "assumeGranted")]
+    AssumeGranted,
+
+    /// 所有权限都视为已拒绝
+    #[serde(rename = "assumeDenied")]
+    AssumeDenied,
+
+    /// 所有检查和请求都返回 `platformUnsupported` 错误
+    #[serde(rename = "unsupportedError")]
+    UnsupportedError,
+
+    /// 按权限类型返回预设的状态，未列出的权限为 `NotDetermined`
+    ///
+    /// 例如 `{ "scripted": { "camera": "denied", "photoKit": "limited" } }`。
+    /// 请求不会改变预设的状态。
+    #[serde(rename = "scripted")]
+    Scripted(HashMap<PermissionKind, PermissionStatus>),
+}
+
+/// 权限类型
+///
+/// 与 `Permission` 相同，但不区分 PhotoKit 的访问级别，用于按类型启用或禁用权限。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PermissionKind {
+    /// 辅助功能权限
+    #[serde(rename = "accessibility")]
+    Accessibility,
+
+    /// 完全磁盘访问权限
+    #[serde(rename = "fullDiskAccess")]
+    FullDiskAccess,
+
+    /// 屏幕录制权限
+    #[serde(rename = "screenRecording")]
+    ScreenRecording,
+
+    /// 麦克风权限
+    #[serde(rename = "microphone")]
+    Microphone,
+
+    /// 摄像头权限
+    #[serde(rename = "camera")]
+    Camera,
+
+    /// 输入监控权限
+    #[serde(rename = "inputMonitoring")]
+    InputMonitoring,
+
+    /// 照片库权限（所有访问级别）
+    #[serde(rename = "photoKit")]
+    PhotoKit,
+}
+
+impl Permission {
+    /// 获取权限对应的权限类型
+    pub fn kind(self) -> PermissionKind {
+        match self {
+            Permission::Accessibility => PermissionKind::Accessibility,
+            Permission::FullDiskAccess => PermissionKind::FullDiskAccess,
+            Permission::ScreenRecording => PermissionKind::ScreenRecording,
+            Permission::Microphone => PermissionKind::Microphone,
+            Permission::Camera => PermissionKind::Camera,
+            Permission::InputMonitoring => PermissionKind::InputMonitoring,
+            Permission::PhotoKit(_) => PermissionKind::PhotoKit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PhotoKitAccessLevel;
+
+    #[test]
+    fn test_config_defaults() {
+        let config: Config = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.cache_ttl, 30);
+        assert_eq!(config.event_targets, vec!["main".to_string()]);
+        assert_eq!(config.polling_interval, Some(2000));
+        assert_eq!(config.max_polling_interval, Some(30_000));
+        assert_eq!(config.debounce_window, 0);
+        assert_eq!(config.history_capacity, 100);
+        assert!(config.persist_snapshot);
+        assert_eq!(config.fallback_policy, FallbackPolicy::AssumeGranted);
+        assert_eq!(config.enabled_permissions, None);
+
+        // 模块文档中列出的默认值
+        let documented: Config = serde_json::from_value(serde_json::json!({
+            "cacheTtl": 30,
+            "eventTargets": ["main"],
+            "pollingInterval": 2000,
+            "maxPollingInterval": 30000,
+            "debounceWindow": 0,
+            "historyCapacity": 100,
+            "persistSnapshot": true,
+            "fallbackPolicy": "assumeGranted",
+            "enabledPermissions": null
+        }))
+        .unwrap();
+        assert_eq!(documented, Config::default());
+    }
+
+    #[test]
+    fn test_config_deserialization() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "cacheTtl": 5,
+            "eventTargets": ["main", "settings"],
+            "pollingInterval": 2000,
+            "maxPollingInterval": null,
+            "debounceWindow": 500,
+            "historyCapacity": 20,
+            "persistSnapshot": false,
+            "fallbackPolicy": "assumeDenied",
+            "enabledPermissions": ["camera", "photoKit"]
+        }))
+        .unwrap();
+
+        assert_eq!(config.cache_ttl, 5);
+        assert_eq!(config.event_targets, vec!["main", "settings"]);
+        assert_eq!(config.polling_interval, Some(2000));
+        assert_eq!(config.max_polling_interval, None);
+        assert_eq!(config.debounce_window, 500);
+        assert_eq!(config.history_capacity, 20);
+        assert!(!config.persist_snapshot);
+        assert_eq!(config.fallback_policy, FallbackPolicy::AssumeDenied);
+        assert_eq!(
+            config.enabled_permissions,
+            Some(vec![PermissionKind::Camera, PermissionKind::PhotoKit])
+        );
+        assert_eq!(
+            Permission::PhotoKit(PhotoKitAccessLevel::AddOnly).kind(),
+            PermissionKind::PhotoKit
+        );
+    }
+
+    #[test]
+    fn test_fallback_policy_deserialization() {
+        let policy: FallbackPolicy = serde_json::from_value(serde_json::json!({
+            "scripted": { "camera": "denied", "photoKit": "limited" }
+        }))
+        .unwrap();
+        assert_eq!(
+            policy,
+            FallbackPolicy::Scripted(HashMap::from([
+                (PermissionKind::Camera, PermissionStatus::Denied),
+                (PermissionKind::PhotoKit, PermissionStatus::Limited),
+            ]))
+        );
+
+        let policy: FallbackPolicy =
+            serde_json::from_value(serde_json::json!("unsupportedError")).unwrap();
+        assert_eq!(policy, FallbackPolicy::UnsupportedError);
+    }
+}
@@ -0,0 +1,234 @@
+//! 插件命令错误类型
+//!
+//! `PermissionError` 是所有插件命令返回给前端的错误类型。它序列化为带有稳定 `code` 字段的对象，
+//! 前端可以根据 `code` 分支处理，而不必匹配本地化的错误消息。
+
+use crate::{Permission, PhotoKitListenerError, PhotoKitManagerError, REQUEST_TIMEOUT};
+use serde::Serialize;
+
+#[cfg(target_os = "macos")]
+use crate::photokit_bridge::PhotoKitBridgeError;
+
+/// 插件命令错误
+///
+/// 序列化后的形式为 `{ "code": "timeout", "permission": "camera", "timeoutMs": 120000 }`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code")]
+pub enum PermissionError {
+    /// 当前平台不支持该功能
+    #[serde(rename = "platformUnsupported")]
+    #[error("平台不支持: {feature}")]
+    PlatformUnsupported { feature: String },
+
+    /// 系统框架不可用
+    #[serde(rename = "frameworkUnavailable")]
+    #[error("{framework} 框架不可用")]
+    FrameworkUnavailable { framework: String },
+
+    /// Info.plist 缺少请求权限所需的用途说明
+    #[serde(rename = "missingUsageDescription")]
+    #[error("Info.plist 缺少 {key}，无法请求权限")]
+    MissingUsageDescription { permission: Permission, key: String },
+
+    /// 等待用户响应系统对话框超时
+    #[serde(rename = "timeout")]
+    #[error("权限请求超时（{timeout_ms} 毫秒）")]
+    Timeout {
+        permission: Option<Permission>,
+        #[serde(rename = "timeoutMs")]
+        timeout_ms: u64,
+    },
+
+    /// 权限检查或请求失败
+    #[serde(rename = "requestFailed")]
+    #[error("权限请求失败: {message}")]
+    RequestFailed {
+        permission: Option<Permission>,
+        message: String,
+    },
+
+    /// 该权限未在插件配置中启用
+    #[serde(rename = "permissionDisabled")]
+    #[error("权限未启用: {permission:?}")]
+    PermissionDisabled { permission: Permission },
+
+    /// 内部锁定失败
+    #[serde(rename = "lockPoisoned")]
+    #[error("{resource} 锁定失败")]
+    LockPoisoned { resource: String },
+}
+
+impl PermissionError {
+    /// 创建不关联具体权限的请求失败错误
+    pub fn request_failed(message: impl Into<String>) -> Self {
+        PermissionError::RequestFailed {
+            permission: None,
+            message: message.into(),
+        }
+    }
+
+    /// 创建使用默认超时时间的超时错误
+    pub fn timeout(permission: Option<Permission>) -> Self {
+        PermissionError::Timeout {
+            permission,
+            timeout_ms: REQUEST_TIMEOUT.as_millis() as u64,
+        }
+    }
+
+    /// 为尚未关联权限的错误补充权限上下文
+    pub fn with_permission(mut self, permission: Permission) -> Self {
+        match &mut self {
+            PermissionError::Timeout {
+                permission: target, ..
+            }
+            | PermissionError::RequestFailed {
+                permission: target, ..
+            } => {
+                target.get_or_insert(permission);
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+impl From<PhotoKitManagerError> for PermissionError {
+    fn from(error: PhotoKitManagerError) -> Self {
+        match error {
+            PhotoKitManagerError::PlatformNotSupported => PermissionError::PlatformUnsupported {
+                feature: "PhotoKit".to_string(),
+            },
+            PhotoKitManagerError::CheckFailed(message)
+            | PhotoKitManagerError::RequestFailed(message) => {
+                PermissionError::request_failed(message)
+            }
+            PhotoKitManagerError::CacheError(_) => PermissionError::LockPoisoned {
+                resource: "permissionCache".to_string(),
+            },
+            PhotoKitManagerError::Permission(error) => error,
+            #[cfg(target_os = "macos")]
+            PhotoKitManagerError::BridgeError(error) => error.into(),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<PhotoKitBridgeError> for PermissionError {
+    fn from(error: PhotoKitBridgeError) -> Self {
+        match error {
+            PhotoKitBridgeError::FrameworkUnavailable => PermissionError::FrameworkUnavailable {
+                framework: "PhotoKit".to_string(),
+            },
+            PhotoKitBridgeError::RequestTimeout => PermissionError::timeout(None),
+            PhotoKitBridgeError::RequestFailed(message) => PermissionError::request_failed(message),
+            error @ (PhotoKitBridgeError::InvalidAccessLevel(_)
+            | PhotoKitBridgeError::InvalidAuthorizationStatus(_)) => {
+                PermissionError::request_failed(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<PhotoKitListenerError> for PermissionError {
+    fn from(error: PhotoKitListenerError) -> Self {
+        match error {
+            PhotoKitListenerError::LockFailed => PermissionError::LockPoisoned {
+                resource: "listeners".to_string(),
+            },
+            PhotoKitListenerError::PlatformNotSupported => PermissionError::PlatformUnsupported {
+                feature: "permissionListener".to_string(),
+            },
+            error @ (PhotoKitListenerError::ListenerNotFound(_)
+            | PhotoKitListenerError::ListenerAlreadyExists(_)
+            | PhotoKitListenerError::EventEmitFailed(_)) => {
+                PermissionError::request_failed(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<tauri::Error> for PermissionError {
+    fn from(error: tauri::Error) -> Self {
+        PermissionError::request_failed(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PhotoKitAccessLevel;
+
+    #[test]
+    fn test_error_codes_serialization() {
+        let error = PermissionError::timeout(Some(Permission::Camera));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "timeout",
+                "permission": "camera",
+                "timeoutMs": REQUEST_TIMEOUT.as_millis() as u64,
+            })
+        );
+
+        let error = PermissionError::MissingUsageDescription {
+            permission: Permission::PhotoKit(PhotoKitAccessLevel::AddOnly),
+            key: "NSPhotoLibraryAddUsageDescription".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "missingUsageDescription",
+                "permission": { "photoKit": "addOnly" },
+                "key": "NSPhotoLibraryAddUsageDescription",
+            })
+        );
+
+        let error = PermissionError::LockPoisoned {
+            resource: "listeners".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["code"],
+            "lockPoisoned"
+        );
+    }
+
+    #[test]
+    fn test_from_inner_errors() {
+        assert_eq!(
+            PermissionError::from(PhotoKitManagerError::PlatformNotSupported),
+            PermissionError::PlatformUnsupported {
+                feature: "PhotoKit".to_string()
+            }
+        );
+        assert!(matches!(
+            PermissionError::from(PhotoKitManagerError::CacheError("poisoned".to_string())),
+            PermissionError::LockPoisoned { .. }
+        ));
+        assert_eq!(
+            PermissionError::from(PhotoKitListenerError::ListenerNotFound("id".to_string())),
+            PermissionError::request_failed("监听器不存在: id")
+        );
+    }
+
+    #[test]
+    fn test_with_permission_keeps_existing_context() {
+        let error = PermissionError::request_failed("boom").with_permission(Permission::Camera);
+        assert_eq!(
+            error,
+            PermissionError::RequestFailed {
+                permission: Some(Permission::Camera),
+                message: "boom".to_string(),
+            }
+        );
+
+        let error = PermissionError::timeout(Some(Permission::Microphone))
+            .with_permission(Permission::Camera);
+        assert!(matches!(
+            error,
+            PermissionError::Timeout {
+                permission: Some(Permission::Microphone),
+                ..
+            }
+        ));
+    }
+}
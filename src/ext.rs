@@ -0,0 +1,325 @@
+//! Rust 侧 API
+//!
+//! 应用的 Rust 代码通过 `MacosPermissionsExt` 访问插件，不必经过前端即可检查权限或订阅权限变化：
+//!
+//! ```no_run
+//! use tauri_plugin_macos_permissions_with_photokit::{MacosPermissionsExt, Permission, PermissionStatus};
+//!
+//! # fn example(app: &tauri::AppHandle) {
+//! let guard = app
+//!     .macos_permissions()
+//!     .on_change(Permission::ScreenRecording, |event| {
+//!         if event.status == PermissionStatus::Denied {
+//!             // 停止屏幕录制
+//!         }
+//!     })
+//!     .expect("failed to subscribe");
+//! # drop(guard);
+//! # }
+//! ```
+
+use crate::{
+    probe_watched_permissions, ChangeCause, Permission, PermissionChangeGuard,
+    PermissionChangedEvent, PermissionError, PermissionStatus, PhotoKitPermissionListener,
+    PhotoKitPermissionManager,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{Manager, Runtime};
+
+/// 插件的 Rust 侧入口
+///
+/// 与前端命令共享同一个权限管理器和监听器注册表。
+pub struct MacosPermissions<R: Runtime> {
+    manager: PhotoKitPermissionManager,
+    listener: PhotoKitPermissionListener<R>,
+    rechecking: Arc<AtomicBool>,
+}
+
+/// 后台重新检查进行中的标记，被丢弃时允许下一次后台重新检查
+pub(crate) struct RecheckInFlight(Arc<AtomicBool>);
+
+impl Drop for RecheckInFlight {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<R: Runtime> MacosPermissions<R> {
+    /// 创建新的 Rust 侧入口
+    ///
+    /// # Arguments
+    /// * `manager` - 插件共享的权限管理器
+    /// * `listener` - 插件共享的监听器注册表
+    pub fn new(
+        manager: PhotoKitPermissionManager,
+        listener: PhotoKitPermissionListener<R>,
+    ) -> Self {
+        Self {
+            manager,
+            listener,
+            rechecking: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 检查指定权限的授权状态
+    pub fn check_permission(
+        &self,
+        permission: Permission,
+    ) -> Result<PermissionStatus, PermissionError> {
+        self.manager
+            .check_permission(permission)
+            .map_err(|e| PermissionError::from(e).with_permission(permission))
+    }
+
+    /// 请求指定权限，阻塞直到用户响应
+    ///
+    /// 请求改变了被监听权限的状态时，以 `userRequest` 为原因分发变化事件。
+    pub fn request_permission(
+        &self,
+        permission: Permission,
+    ) -> Result<PermissionStatus, PermissionError> {
+        let status = self
+            .manager
+            .request_permission(permission)
+            .map_err(|e| PermissionError::from(e).with_permission(permission))?;
+
+        // 事件分发失败不影响请求结果
+        let _ = self
+            .listener
+            .observe_status(permission, status, ChangeCause::UserRequest);
+
+        // 用户可能随后在系统设置中修改授权，恢复快速轮询
+        self.listener.poll_soon();
+
+        Ok(status)
+    }
+
+    /// 订阅指定权限的状态变化
+    ///
+    /// 回调由与前端事件相同的变化检测驱动，在后台线程上调用。
+    /// 订阅时先以 `initial` 为原因收到一次当前状态。
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `callback` - 每次状态变化时调用的回调
+    ///
+    /// # Returns
+    /// 返回订阅守卫，守卫被丢弃时取消订阅
+    pub fn on_change<F>(
+        &self,
+        permission: Permission,
+        callback: F,
+    ) -> Result<PermissionChangeGuard<R>, PermissionError>
+    where
+        F: Fn(&PermissionChangedEvent) + Send + Sync + 'static,
+    {
+        self.listener
+            .on_change(permission, callback)
+            .map_err(PermissionError::from)
+    }
+
+    /// 清除状态缓存并立即重新检查所有被监听的权限
+    ///
+    /// 插件在窗口获得焦点和点击 Dock 图标重新打开应用时自动调用，用户在系统设置中修改权限后切回应用，
+    /// 无需等待轮询即可收到变化事件。与检测线程的检查按顺序进行，不会用较早的状态覆盖较新的状态。
+    ///
+    /// # Returns
+    /// 检测到的状态变化数量
+    pub fn recheck(&self) -> Result<usize, PermissionError> {
+        self.manager.clear_cache(None)?;
+        let changes = probe_watched_permissions(&self.manager, &self.listener)?;
+
+        // 获得焦点后状态更可能继续变化，恢复快速轮询
+        self.listener.poll_soon();
+        Ok(changes)
+    }
+
+    /// 标记开始一次后台重新检查
+    ///
+    /// # Returns
+    /// 已有后台重新检查在进行时返回 None，调用方应跳过本次检查
+    pub(crate) fn begin_background_recheck(&self) -> Option<RecheckInFlight> {
+        if self.rechecking.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        Some(RecheckInFlight(self.rechecking.clone()))
+    }
+
+    /// 插件共享的权限管理器
+    pub fn manager(&self) -> &PhotoKitPermissionManager {
+        &self.manager
+    }
+
+    /// 插件共享的监听器注册表
+    pub fn listener(&self) -> &PhotoKitPermissionListener<R> {
+        &self.listener
+    }
+}
+
+/// 通过 `App`、`AppHandle`、`Window` 等访问插件 Rust 侧 API 的扩展
+pub trait MacosPermissionsExt<R: Runtime> {
+    /// 获取插件的 Rust 侧入口
+    ///
+    /// # Panics
+    /// 插件未注册时 panic
+    fn macos_permissions(&self) -> &MacosPermissions<R>;
+}
+
+impl<R: Runtime, T: Manager<R>> MacosPermissionsExt<R> for T {
+    fn macos_permissions(&self) -> &MacosPermissions<R> {
+        self.state::<MacosPermissions<R>>().inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_with_backend, FakePermissionBackend, PhotoKitAccessLevel};
+    use std::sync::{Arc, Mutex};
+    use tauri::test::{mock_builder, mock_context, noop_assets};
+
+    #[test]
+    fn test_background_rechecks_coalesce() {
+        let app = mock_builder()
+            .plugin(init_with_backend(Arc::new(FakePermissionBackend::new())))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+        let permissions = app.macos_permissions();
+
+        let in_flight = permissions.begin_background_recheck();
+        assert!(in_flight.is_some());
+        assert!(permissions.begin_background_recheck().is_none());
+
+        // 上一次检查完成后可以再次开始
+        drop(in_flight);
+        assert!(permissions.begin_background_recheck().is_some());
+    }
+
+    #[test]
+    fn test_on_change_guard() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_builder()
+            .plugin(init_with_backend(backend.clone()))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = received.clone();
+        let guard = app
+            .macos_permissions()
+            .on_change(Permission::ScreenRecording, move |event| {
+                events.lock().unwrap().push(event.status);
+            })
+            .unwrap();
+
+        // 订阅时立即收到当前状态
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PermissionStatus::NotDetermined]
+        );
+
+        // Rust 回调与前端事件由同一个监听器注册表分发
+        let listener = app.macos_permissions().listener();
+        assert_eq!(listener.get_active_listeners().unwrap().len(), 1);
+        listener
+            .handle_status_change(Permission::ScreenRecording, PermissionStatus::Denied)
+            .unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PermissionStatus::NotDetermined, PermissionStatus::Denied]
+        );
+
+        drop(guard);
+        assert!(listener.get_active_listeners().unwrap().is_empty());
+        listener
+            .handle_status_change(Permission::ScreenRecording, PermissionStatus::Authorized)
+            .unwrap();
+        assert_eq!(received.lock().unwrap().len(), 2);
+
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        assert_eq!(
+            app.macos_permissions().check_permission(Permission::Camera),
+            Ok(PermissionStatus::Authorized)
+        );
+    }
+
+    #[test]
+    fn test_recheck_invalidates_cache_and_reports_changes() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_builder()
+            .plugin(init_with_backend(backend.clone()))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+        let permissions = app.macos_permissions();
+        let read = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = received.clone();
+        let _guard = permissions
+            .on_change(Permission::ScreenRecording, move |event| {
+                events.lock().unwrap().push(event.clone());
+            })
+            .unwrap();
+
+        // 订阅时补发的当前状态已作为基准
+        assert_eq!(permissions.recheck(), Ok(0));
+        assert_eq!(
+            permissions.check_permission(read),
+            Ok(PermissionStatus::NotDetermined)
+        );
+
+        backend.set_status(Permission::ScreenRecording, PermissionStatus::Authorized);
+        backend.set_status(read, PermissionStatus::Authorized);
+        assert_eq!(permissions.recheck(), Ok(1));
+
+        let events = received.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].cause, ChangeCause::Initial);
+        assert_eq!(events[1].status, PermissionStatus::Authorized);
+        assert_eq!(events[1].cause, ChangeCause::ExternalChange);
+
+        // 缓存的 PhotoKit 状态已失效
+        assert_eq!(
+            permissions.check_permission(read),
+            Ok(PermissionStatus::Authorized)
+        );
+    }
+
+    #[test]
+    fn test_request_reports_user_request_cause() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_builder()
+            .plugin(init_with_backend(backend))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = received.clone();
+        let _guard = app
+            .macos_permissions()
+            .on_change(Permission::Camera, move |event| {
+                events.lock().unwrap().push(event.clone());
+            })
+            .unwrap();
+
+        assert_eq!(
+            app.macos_permissions()
+                .request_permission(Permission::Camera),
+            Ok(PermissionStatus::Authorized)
+        );
+
+        // 订阅时补发的当前状态作为请求前的基准
+        let events = received.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].cause, ChangeCause::Initial);
+        assert_eq!(events[0].previous_status, None);
+        assert_eq!(events[0].status, PermissionStatus::NotDetermined);
+        assert_eq!(events[1].cause, ChangeCause::UserRequest);
+        assert_eq!(
+            events[1].previous_status,
+            Some(PermissionStatus::NotDetermined)
+        );
+        assert_eq!(events[1].status, PermissionStatus::Authorized);
+    }
+}
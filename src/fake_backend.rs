@@ -0,0 +1,325 @@
+//! 内存模拟权限后端
+//!
+//! `FakePermissionBackend` 把所有权限状态保存在内存中，不调用任何系统 API，
+//! 可以在任何平台（包括 Linux CI）上驱动插件命令和权限管理器的完整流程。
+
+use crate::{
+    Permission, PermissionBackend, PermissionError, PermissionStatus, PhotoKitAccessLevel,
+    PhotoKitAuthorizationStatus, PhotoKitManagerError,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 模拟后端的内部状态
+#[derive(Debug, Clone)]
+struct FakeState {
+    statuses: HashMap<Permission, PermissionStatus>,
+    photos_count: u64,
+    grant_on_request: bool,
+    request_count: usize,
+    missing_usage_descriptions: HashSet<&'static str>,
+    check_delays: HashMap<Permission, Duration>,
+    request_delay: Duration,
+}
+
+impl Default for FakeState {
+    fn default() -> Self {
+        Self {
+            statuses: HashMap::new(),
+            photos_count: 0,
+            grant_on_request: true,
+            request_count: 0,
+            missing_usage_descriptions: HashSet::new(),
+            check_delays: HashMap::new(),
+            request_delay: Duration::ZERO,
+        }
+    }
+}
+
+/// 内存模拟权限后端
+///
+/// 初始状态下所有权限均为 `NotDetermined`。
+/// 默认情况下任何请求都会授予对应权限，可以通过 `set_grant_on_request(false)` 模拟用户拒绝。
+#[derive(Debug, Default)]
+pub struct FakePermissionBackend {
+    state: Mutex<FakeState>,
+}
+
+impl FakePermissionBackend {
+    /// 创建新的模拟后端实例
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取指定权限的当前状态
+    pub fn status(&self, permission: Permission) -> PermissionStatus {
+        self.with_state(|state| {
+            state
+                .statuses
+                .get(&permission)
+                .copied()
+                .unwrap_or(PermissionStatus::NotDetermined)
+        })
+    }
+
+    /// 设置指定权限的状态
+    pub fn set_status(&self, permission: Permission, status: PermissionStatus) {
+        self.with_state(|state| {
+            state.statuses.insert(permission, status);
+        });
+    }
+
+    /// 设置照片库中的照片数量
+    pub fn set_photos_count(&self, count: u64) {
+        self.with_state(|state| state.photos_count = count);
+    }
+
+    /// 设置请求权限时是否授予
+    pub fn set_grant_on_request(&self, grant: bool) {
+        self.with_state(|state| state.grant_on_request = grant);
+    }
+
+    /// 模拟 Info.plist 中是否存在指定权限的用途说明
+    ///
+    /// 默认所有用途说明都存在；移除后，请求该权限会返回 `MissingUsageDescription` 错误。
+    pub fn set_usage_description(&self, permission: Permission, present: bool) {
+        let Some(key) = permission.usage_description_key() else {
+            return;
+        };
+
+        self.with_state(|state| {
+            if present {
+                state.missing_usage_descriptions.remove(key);
+            } else {
+                state.missing_usage_descriptions.insert(key);
+            }
+        });
+    }
+
+    /// 设置检查指定权限时的延迟，用于模拟耗时的系统调用
+    pub fn set_check_delay(&self, permission: Permission, delay: Duration) {
+        self.with_state(|state| {
+            state.check_delays.insert(permission, delay);
+        });
+    }
+
+    /// 设置每次请求的延迟，用于模拟用户在系统对话框上思考的时间
+    pub fn set_request_delay(&self, delay: Duration) {
+        self.with_state(|state| state.request_delay = delay);
+    }
+
+    /// 获取后端收到的权限请求总次数
+    pub fn request_count(&self) -> usize {
+        self.with_state(|state| state.request_count)
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut FakeState) -> T) -> T {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut state)
+    }
+
+    /// 模拟一次权限检查，在返回状态之前等待设置的延迟
+    fn check(&self, permission: Permission) -> PermissionStatus {
+        let delay = self.with_state(|state| state.check_delays.get(&permission).copied());
+        if let Some(delay) = delay {
+            std::thread::sleep(delay);
+        }
+
+        self.status(permission)
+    }
+
+    /// 记录一次请求，并根据 `grant_on_request` 更新权限状态
+    fn record_request(&self, permission: Permission) -> Result<PermissionStatus, PermissionError> {
+        let delay = self.with_state(|state| state.request_delay);
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+
+        self.with_state(|state| {
+            if let Some(key) = permission
+                .usage_description_key()
+                .filter(|key| state.missing_usage_descriptions.contains(key))
+            {
+                return Err(PermissionError::MissingUsageDescription {
+                    permission,
+                    key: key.to_string(),
+                });
+            }
+
+            state.request_count += 1;
+            let status = if state.grant_on_request {
+                PermissionStatus::Authorized
+            } else {
+                PermissionStatus::Denied
+            };
+            state.statuses.insert(permission, status);
+            Ok(status)
+        })
+    }
+}
+
+impl PermissionBackend for FakePermissionBackend {
+    fn check_accessibility(&self) -> PermissionStatus {
+        self.check(Permission::Accessibility)
+    }
+
+    fn request_accessibility(&self) {
+        let _ = self.record_request(Permission::Accessibility);
+    }
+
+    fn check_full_disk_access(&self) -> PermissionStatus {
+        self.check(Permission::FullDiskAccess)
+    }
+
+    fn request_full_disk_access(&self) -> Result<(), PermissionError> {
+        self.record_request(Permission::FullDiskAccess).map(|_| ())
+    }
+
+    fn check_screen_recording(&self) -> PermissionStatus {
+        self.check(Permission::ScreenRecording)
+    }
+
+    fn request_screen_recording(&self) {
+        let _ = self.record_request(Permission::ScreenRecording);
+    }
+
+    fn check_microphone(&self) -> PermissionStatus {
+        self.check(Permission::Microphone)
+    }
+
+    fn request_microphone(&self) -> Result<PermissionStatus, PermissionError> {
+        self.record_request(Permission::Microphone)
+    }
+
+    fn check_camera(&self) -> PermissionStatus {
+        self.check(Permission::Camera)
+    }
+
+    fn request_camera(&self) -> Result<PermissionStatus, PermissionError> {
+        self.record_request(Permission::Camera)
+    }
+
+    fn check_input_monitoring(&self) -> PermissionStatus {
+        self.check(Permission::InputMonitoring)
+    }
+
+    fn request_input_monitoring(&self) -> Result<(), PermissionError> {
+        self.record_request(Permission::InputMonitoring).map(|_| ())
+    }
+
+    fn check_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        Ok(self.check(Permission::PhotoKit(access_level)).into())
+    }
+
+    fn request_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        Ok(self
+            .record_request(Permission::PhotoKit(access_level))?
+            .into())
+    }
+
+    fn is_photokit_available(&self) -> bool {
+        true
+    }
+
+    fn get_photos_count(&self) -> Result<u64, PhotoKitManagerError> {
+        let authorized = self
+            .status(Permission::PhotoKit(PhotoKitAccessLevel::Read))
+            .is_granted();
+
+        if !authorized {
+            return Err(PhotoKitManagerError::CheckFailed(
+                "需要照片库读取权限才能查询照片数量".to_string(),
+            ));
+        }
+
+        Ok(self.with_state(|state| state.photos_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_backend_defaults() {
+        let backend = FakePermissionBackend::new();
+
+        assert_eq!(
+            backend.check_accessibility(),
+            PermissionStatus::NotDetermined
+        );
+        assert_eq!(
+            backend.check_screen_recording(),
+            PermissionStatus::NotDetermined
+        );
+        assert_eq!(
+            backend.check_photokit(PhotoKitAccessLevel::Read).unwrap(),
+            PhotoKitAuthorizationStatus::NotDetermined
+        );
+        assert_eq!(backend.request_count(), 0);
+    }
+
+    #[test]
+    fn test_fake_backend_requests() {
+        let backend = FakePermissionBackend::new();
+
+        backend.request_screen_recording();
+        assert_eq!(
+            backend.check_screen_recording(),
+            PermissionStatus::Authorized
+        );
+
+        backend.set_grant_on_request(false);
+        assert_eq!(backend.request_camera().unwrap(), PermissionStatus::Denied);
+        assert_eq!(backend.check_camera(), PermissionStatus::Denied);
+        assert_eq!(
+            backend
+                .request_photokit(PhotoKitAccessLevel::ReadWrite)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Denied
+        );
+        assert_eq!(backend.request_count(), 3);
+    }
+
+    #[test]
+    fn test_fake_backend_missing_usage_description() {
+        let backend = FakePermissionBackend::new();
+        backend.set_usage_description(Permission::Camera, false);
+
+        assert_eq!(
+            backend.request_camera(),
+            Err(PermissionError::MissingUsageDescription {
+                permission: Permission::Camera,
+                key: "NSCameraUsageDescription".to_string(),
+            })
+        );
+        assert_eq!(backend.check_camera(), PermissionStatus::NotDetermined);
+        assert_eq!(backend.request_count(), 0);
+
+        backend.set_usage_description(Permission::Camera, true);
+        assert_eq!(backend.request_camera(), Ok(PermissionStatus::Authorized));
+    }
+
+    #[test]
+    fn test_fake_backend_photos_count_requires_read_access() {
+        let backend = FakePermissionBackend::new();
+        backend.set_photos_count(42);
+        assert!(backend.get_photos_count().is_err());
+
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Limited,
+        );
+        assert_eq!(backend.get_photos_count().unwrap(), 42);
+    }
+}
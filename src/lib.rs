@@ -1,25 +1,44 @@
 use serde::{Deserialize, Serialize};
-use tauri::{
-    generate_handler,
-    plugin::{Builder, TauriPlugin},
-    Runtime,
-};
+use tauri::{plugin::TauriPlugin, Runtime};
 
+mod backend;
+mod builder;
+mod change_detector;
+mod clock;
 mod commands;
+mod config;
+mod error;
+mod ext;
+mod fake_backend;
+
+#[cfg(target_os = "macos")]
+mod macos_backend;
 
 #[cfg(target_os = "macos")]
 mod photokit_bridge;
 
 mod photokit_manager;
 
-#[cfg(target_os = "macos")]
 mod photokit_listener;
 
+mod snapshot;
+
+pub use backend::*;
+pub use builder::*;
+pub use change_detector::*;
+pub use clock::*;
 pub use commands::*;
+pub use config::*;
+pub use error::*;
+pub use ext::*;
+pub use fake_backend::*;
 pub use photokit_manager::*;
 
 #[cfg(target_os = "macos")]
+pub use macos_backend::*;
+
 pub use photokit_listener::*;
+pub use snapshot::*;
 
 /// PhotoKit 访问权限级别
 ///
@@ -75,6 +94,68 @@ pub enum PhotoKitAuthorizationStatus {
     Limited,
 }
 
+/// 插件支持的系统权限
+///
+/// 用于 `check_permission` / `request_permission` 等通用命令，
+/// 前端可以用同一套命令驱动所有权限，而无需为每种权限单独调用不同的命令。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    /// 辅助功能权限
+    #[serde(rename = "accessibility")]
+    Accessibility,
+
+    /// 完全磁盘访问权限
+    #[serde(rename = "fullDiskAccess")]
+    FullDiskAccess,
+
+    /// 屏幕录制权限
+    #[serde(rename = "screenRecording")]
+    ScreenRecording,
+
+    /// 麦克风权限
+    #[serde(rename = "microphone")]
+    Microphone,
+
+    /// 摄像头权限
+    #[serde(rename = "camera")]
+    Camera,
+
+    /// 输入监控权限
+    #[serde(rename = "inputMonitoring")]
+    InputMonitoring,
+
+    /// 指定访问级别的照片库权限
+    #[serde(rename = "photoKit")]
+    PhotoKit(PhotoKitAccessLevel),
+}
+
+/// 通用权限授权状态
+///
+/// 以 `PhotoKitAuthorizationStatus` 为模型，适用于插件支持的所有权限。
+/// 前端可以据此区分“从未询问”（显示请求提示）与“已拒绝”（引导用户前往系统设置）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PermissionStatus {
+    /// 未确定 - 用户尚未被询问过该权限
+    #[serde(rename = "notDetermined")]
+    NotDetermined,
+
+    /// 受限 - 由于家长控制或设备管理策略，应用无法获得该权限
+    #[serde(rename = "restricted")]
+    Restricted,
+
+    /// 已拒绝 - 用户明确拒绝了该权限，或系统只能报告“未授予”
+    #[serde(rename = "denied")]
+    Denied,
+
+    /// 已授权 - 用户已授予该权限
+    #[serde(rename = "authorized")]
+    Authorized,
+
+    /// 有限访问 - 仅用于 PhotoKit，用户只授予了部分照片的访问权限
+    #[serde(rename = "limited")]
+    Limited,
+}
+
 /// 权限状态变化事件
 ///
 /// 当照片库权限状态发生变化时，通过此结构体传递事件信息。
@@ -84,13 +165,67 @@ pub struct PermissionStatusChangeEvent {
     /// 新的权限状态
     pub new_status: PhotoKitAuthorizationStatus,
 
+    /// 变化前的权限状态，之前没有观察到状态时为 None
+    pub previous_status: Option<PhotoKitAuthorizationStatus>,
+
     /// 相关的权限级别
     pub access_level: PhotoKitAccessLevel,
 
+    /// 状态变化的原因
+    pub cause: ChangeCause,
+
+    /// 事件序号，在一次运行中单调递增，可用于在时间戳相同时排序
+    pub sequence: u64,
+
     /// 变化发生的时间戳（Unix 时间戳，毫秒）
     pub timestamp: u64,
 }
 
+/// 通用权限变化事件
+///
+/// 任意被监听的权限状态发生变化时，通过 `permission-changed` 事件发送到前端。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionChangedEvent {
+    /// 状态发生变化的权限
+    pub permission: Permission,
+
+    /// 变化前的权限状态，之前没有观察到状态时为 None
+    #[serde(rename = "previousStatus")]
+    pub previous_status: Option<PermissionStatus>,
+
+    /// 新的权限状态
+    pub status: PermissionStatus,
+
+    /// 状态变化的原因
+    pub cause: ChangeCause,
+
+    /// 事件序号，在一次运行中单调递增，可用于在时间戳相同时排序
+    pub sequence: u64,
+
+    /// 变化发生的时间戳（Unix 时间戳，毫秒）
+    pub timestamp: u64,
+}
+
+/// 权限状态变化的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeCause {
+    /// 插件发起的权限请求得到了用户的响应
+    #[serde(rename = "userRequest")]
+    UserRequest,
+
+    /// 在插件之外发生的变化，例如用户在系统设置中修改了授权
+    #[serde(rename = "externalChange")]
+    ExternalChange,
+
+    /// 启动时与上次运行保存的状态比较发现的变化
+    #[serde(rename = "startupReconciliation")]
+    StartupReconciliation,
+
+    /// 监听器注册时补发的当前状态，不代表状态发生了变化
+    #[serde(rename = "initial")]
+    Initial,
+}
+
 impl PhotoKitAccessLevel {
     /// 将 PhotoKitAccessLevel 转换为对应的 PhotoKit 原生常量值
     ///
@@ -174,6 +309,123 @@ impl PhotoKitAuthorizationStatus {
     }
 }
 
+impl Permission {
+    /// 插件支持的所有权限，PhotoKit 的每个访问级别各占一项
+    pub const ALL: [Permission; 9] = [
+        Permission::Accessibility,
+        Permission::FullDiskAccess,
+        Permission::ScreenRecording,
+        Permission::Microphone,
+        Permission::Camera,
+        Permission::InputMonitoring,
+        Permission::PhotoKit(PhotoKitAccessLevel::Read),
+        Permission::PhotoKit(PhotoKitAccessLevel::ReadWrite),
+        Permission::PhotoKit(PhotoKitAccessLevel::AddOnly),
+    ];
+
+    /// 请求该权限前 Info.plist 中必须提供的用途说明键
+    ///
+    /// # Returns
+    /// 对应的 Info.plist 键；如果该权限不会弹出系统对话框（例如需要用户前往系统设置手动开启），返回 None
+    pub fn usage_description_key(self) -> Option<&'static str> {
+        match self {
+            Permission::Microphone => Some("NSMicrophoneUsageDescription"),
+            Permission::Camera => Some("NSCameraUsageDescription"),
+            Permission::PhotoKit(PhotoKitAccessLevel::AddOnly) => {
+                Some("NSPhotoLibraryAddUsageDescription")
+            }
+            Permission::PhotoKit(_) => Some("NSPhotoLibraryUsageDescription"),
+            Permission::Accessibility
+            | Permission::FullDiskAccess
+            | Permission::ScreenRecording
+            | Permission::InputMonitoring => None,
+        }
+    }
+}
+
+impl PermissionStatus {
+    /// 检查权限状态是否表示已授予（包括完全授权和有限授权）
+    pub fn is_granted(self) -> bool {
+        matches!(
+            self,
+            PermissionStatus::Authorized | PermissionStatus::Limited
+        )
+    }
+
+    /// 从只能报告“是否授予”的系统 API 结果创建状态
+    ///
+    /// # Returns
+    /// `true` 对应 `Authorized`，`false` 对应 `Denied`
+    pub fn from_granted(granted: bool) -> Self {
+        if granted {
+            PermissionStatus::Authorized
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+
+    /// 从 AVAuthorizationStatus 原生值创建状态
+    ///
+    /// # Arguments
+    /// * `value` - AVAuthorizationStatus 的整数值：
+    ///   0 (NotDetermined)、1 (Restricted)、2 (Denied)、3 (Authorized)
+    ///
+    /// # Returns
+    /// 对应的 PermissionStatus，如果值无效则返回 None
+    pub fn from_av_native_value(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(PermissionStatus::NotDetermined),
+            1 => Some(PermissionStatus::Restricted),
+            2 => Some(PermissionStatus::Denied),
+            3 => Some(PermissionStatus::Authorized),
+            _ => None,
+        }
+    }
+
+    /// 从 IOHIDCheckAccess 返回的 IOHIDAccessType 创建状态
+    ///
+    /// # Arguments
+    /// * `value` - IOHIDAccessType 的整数值：
+    ///   0 (kIOHIDAccessTypeGranted)、1 (kIOHIDAccessTypeDenied)、2 (kIOHIDAccessTypeUnknown)
+    pub fn from_iohid_access(value: u32) -> Self {
+        match value {
+            0 => PermissionStatus::Authorized,
+            1 => PermissionStatus::Denied,
+            _ => PermissionStatus::NotDetermined,
+        }
+    }
+}
+
+impl From<PhotoKitAuthorizationStatus> for PermissionStatus {
+    fn from(status: PhotoKitAuthorizationStatus) -> Self {
+        match status {
+            PhotoKitAuthorizationStatus::NotDetermined => PermissionStatus::NotDetermined,
+            PhotoKitAuthorizationStatus::Restricted => PermissionStatus::Restricted,
+            PhotoKitAuthorizationStatus::Denied => PermissionStatus::Denied,
+            PhotoKitAuthorizationStatus::Authorized => PermissionStatus::Authorized,
+            PhotoKitAuthorizationStatus::Limited => PermissionStatus::Limited,
+        }
+    }
+}
+
+impl From<PermissionStatus> for PhotoKitAuthorizationStatus {
+    fn from(status: PermissionStatus) -> Self {
+        match status {
+            PermissionStatus::NotDetermined => PhotoKitAuthorizationStatus::NotDetermined,
+            PermissionStatus::Restricted => PhotoKitAuthorizationStatus::Restricted,
+            PermissionStatus::Denied => PhotoKitAuthorizationStatus::Denied,
+            PermissionStatus::Authorized => PhotoKitAuthorizationStatus::Authorized,
+            PermissionStatus::Limited => PhotoKitAuthorizationStatus::Limited,
+        }
+    }
+}
+
+impl From<PhotoKitAccessLevel> for Permission {
+    fn from(access_level: PhotoKitAccessLevel) -> Self {
+        Permission::PhotoKit(access_level)
+    }
+}
+
 impl PermissionStatusChangeEvent {
     /// 创建新的权限状态变化事件
     ///
@@ -182,42 +434,100 @@ impl PermissionStatusChangeEvent {
     /// * `access_level` - 相关的权限级别
     ///
     /// # Returns
-    /// 新的 PermissionStatusChangeEvent 实例，时间戳为当前时间
+    /// 新的 PermissionStatusChangeEvent 实例，时间戳为当前时间，原因为外部变化
     pub fn new(new_status: PhotoKitAuthorizationStatus, access_level: PhotoKitAccessLevel) -> Self {
         Self {
             new_status,
+            previous_status: None,
             access_level,
+            cause: ChangeCause::ExternalChange,
+            sequence: 0,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        }
+    }
+}
+
+impl PermissionChangedEvent {
+    /// 创建新的权限变化事件
+    ///
+    /// # Arguments
+    /// * `permission` - 状态发生变化的权限
+    /// * `previous_status` - 变化前的权限状态
+    /// * `status` - 新的权限状态
+    /// * `cause` - 状态变化的原因
+    /// * `sequence` - 事件序号
+    ///
+    /// # Returns
+    /// 新的 PermissionChangedEvent 实例，时间戳为当前时间
+    pub fn new(
+        permission: Permission,
+        previous_status: Option<PermissionStatus>,
+        status: PermissionStatus,
+        cause: ChangeCause,
+        sequence: u64,
+    ) -> Self {
+        Self {
+            permission,
+            previous_status,
+            status,
+            cause,
+            sequence,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as u64,
         }
     }
+
+    /// 转换为旧的 `photokit-permission-changed` 事件，不是 PhotoKit 权限时返回 None
+    pub fn to_photokit_event(&self) -> Option<PermissionStatusChangeEvent> {
+        let Permission::PhotoKit(access_level) = self.permission else {
+            return None;
+        };
+
+        Some(PermissionStatusChangeEvent {
+            new_status: self.status.into(),
+            previous_status: self.previous_status.map(Into::into),
+            access_level,
+            cause: self.cause,
+            sequence: self.sequence,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+/// 使用当前平台默认权限后端初始化插件
+///
+/// 插件选项从 `tauri.conf.json` 的 `plugins.macos-permissions-with-photokit` 中读取，
+/// 需要在 Rust 侧配置时请使用 `Builder`。
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    Builder::new().build()
 }
 
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("macos-permissions-with-photokit")
-        .invoke_handler(generate_handler![
-            commands::check_accessibility_permission,
-            commands::request_accessibility_permission,
-            commands::check_full_disk_access_permission,
-            commands::request_full_disk_access_permission,
-            commands::check_screen_recording_permission,
-            commands::request_screen_recording_permission,
-            commands::check_microphone_permission,
-            commands::request_microphone_permission,
-            commands::check_camera_permission,
-            commands::request_camera_permission,
-            commands::check_input_monitoring_permission,
-            commands::request_input_monitoring_permission,
-            commands::check_photokit_permission,
-            commands::request_photokit_permission,
-            commands::register_photokit_permission_listener,
-            commands::unregister_photokit_permission_listener,
-            commands::get_photokit_permission_listeners,
-            commands::get_photos_count
-        ])
-        .build()
+/// 使用指定的权限后端初始化插件
+///
+/// 所有命令都会通过此后端检查和请求权限，例如在 CI 中传入 `FakePermissionBackend`
+/// 即可在没有 macOS 系统 API 的环境下测试完整的权限流程。
+///
+/// # Example
+/// ```no_run
+/// use std::sync::Arc;
+/// use tauri_plugin_macos_permissions_with_photokit::{
+///     init_with_backend, FakePermissionBackend, Permission, PermissionStatus,
+/// };
+///
+/// let backend = Arc::new(FakePermissionBackend::new());
+/// backend.set_status(Permission::ScreenRecording, PermissionStatus::Authorized);
+///
+/// tauri::Builder::default().plugin(init_with_backend(backend.clone()));
+/// ```
+pub fn init_with_backend<R: Runtime>(
+    backend: SharedPermissionBackend,
+) -> TauriPlugin<R, Option<Config>> {
+    Builder::new().backend(backend).build()
 }
 
 #[cfg(test)]
@@ -304,6 +614,119 @@ mod tests {
         assert!(event.timestamp > 0);
     }
 
+    #[test]
+    fn test_permission_changed_event_serialization() {
+        let event = PermissionChangedEvent::new(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            Some(PermissionStatus::Limited),
+            PermissionStatus::Authorized,
+            ChangeCause::UserRequest,
+            3,
+        );
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["previousStatus"], "limited");
+        assert_eq!(json["cause"], "userRequest");
+        assert_eq!(json["sequence"], 3);
+
+        let photokit_event = event.to_photokit_event().unwrap();
+        assert_eq!(
+            photokit_event.previous_status,
+            Some(PhotoKitAuthorizationStatus::Limited)
+        );
+        assert_eq!(photokit_event.cause, ChangeCause::UserRequest);
+        assert_eq!(photokit_event.sequence, 3);
+        assert_eq!(photokit_event.timestamp, event.timestamp);
+    }
+
+    #[test]
+    fn test_permission_serde() {
+        // 无参数的权限序列化为字符串
+        let json = serde_json::to_string(&Permission::ScreenRecording).unwrap();
+        assert_eq!(json, "\"screenRecording\"");
+
+        // PhotoKit 权限携带访问级别
+        let json = serde_json::to_string(&Permission::PhotoKit(PhotoKitAccessLevel::Read)).unwrap();
+        assert_eq!(json, r#"{"photoKit":"read"}"#);
+
+        let permission: Permission = serde_json::from_str(r#"{"photoKit":"addOnly"}"#).unwrap();
+        assert_eq!(
+            permission,
+            Permission::PhotoKit(PhotoKitAccessLevel::AddOnly)
+        );
+
+        let permission: Permission = serde_json::from_str("\"fullDiskAccess\"").unwrap();
+        assert_eq!(permission, Permission::FullDiskAccess);
+    }
+
+    #[test]
+    fn test_permission_status_native_conversion() {
+        // AVAuthorizationStatus
+        assert_eq!(
+            PermissionStatus::from_av_native_value(0),
+            Some(PermissionStatus::NotDetermined)
+        );
+        assert_eq!(
+            PermissionStatus::from_av_native_value(1),
+            Some(PermissionStatus::Restricted)
+        );
+        assert_eq!(
+            PermissionStatus::from_av_native_value(2),
+            Some(PermissionStatus::Denied)
+        );
+        assert_eq!(
+            PermissionStatus::from_av_native_value(3),
+            Some(PermissionStatus::Authorized)
+        );
+        assert_eq!(PermissionStatus::from_av_native_value(999), None);
+
+        // IOHIDAccessType
+        assert_eq!(
+            PermissionStatus::from_iohid_access(0),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(
+            PermissionStatus::from_iohid_access(1),
+            PermissionStatus::Denied
+        );
+        assert_eq!(
+            PermissionStatus::from_iohid_access(2),
+            PermissionStatus::NotDetermined
+        );
+
+        assert_eq!(
+            PermissionStatus::from_granted(true),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(
+            PermissionStatus::from_granted(false),
+            PermissionStatus::Denied
+        );
+    }
+
+    #[test]
+    fn test_permission_status_photokit_conversion() {
+        let statuses = [
+            PhotoKitAuthorizationStatus::NotDetermined,
+            PhotoKitAuthorizationStatus::Restricted,
+            PhotoKitAuthorizationStatus::Denied,
+            PhotoKitAuthorizationStatus::Authorized,
+            PhotoKitAuthorizationStatus::Limited,
+        ];
+
+        for status in statuses {
+            let converted = PermissionStatus::from(status);
+            assert_eq!(converted.is_granted(), status.is_authorized());
+            assert_eq!(PhotoKitAuthorizationStatus::from(converted), status);
+
+            // 两种状态的序列化结果保持一致
+            assert_eq!(
+                serde_json::to_string(&converted).unwrap(),
+                serde_json::to_string(&status).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_serde_serialization() {
         // 测试序列化和反序列化
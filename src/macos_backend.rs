@@ -0,0 +1,240 @@
+//! macOS 权限后端
+//!
+//! 此模块通过系统 API 实现 `PermissionBackend`：辅助功能使用 `AXIsProcessTrusted`，
+//! 屏幕录制使用 CoreGraphics，麦克风与摄像头使用 AVFoundation，输入监控使用 IOKit，
+//! PhotoKit 相关操作委托给 `PhotoKitBridge`。
+
+use crate::photokit_bridge::PhotoKitBridge;
+use crate::{
+    Permission, PermissionBackend, PermissionError, PermissionStatus, PhotoKitAccessLevel,
+    PhotoKitAuthorizationStatus, PhotoKitManagerError, REQUEST_TIMEOUT,
+};
+use block2::RcBlock;
+use macos_accessibility_client::accessibility::{
+    application_is_trusted, application_is_trusted_with_prompt,
+};
+use objc2::{
+    class, msg_send,
+    runtime::{AnyObject, Bool},
+};
+use objc2_foundation::{NSHomeDirectory, NSString};
+use std::{fs::read_dir, path::PathBuf, process::Command, sync::mpsc};
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOHIDCheckAccess(request: u32) -> u32;
+}
+
+/// 调用 macOS 系统 API 的权限后端
+pub struct MacOSPermissionBackend {
+    bridge: PhotoKitBridge,
+    /// 用户主目录，用于检查完全磁盘访问权限
+    home_dir: PathBuf,
+}
+
+impl MacOSPermissionBackend {
+    /// 创建新的 macOS 权限后端实例
+    ///
+    /// 用户主目录由 `NSHomeDirectory` 确定。
+    pub fn new() -> Self {
+        Self::with_home_dir(NSHomeDirectory().to_string())
+    }
+
+    /// 使用指定的用户主目录创建 macOS 权限后端实例
+    ///
+    /// # Arguments
+    /// * `home_dir` - 用户主目录，完全磁盘访问权限通过读取其中受保护的目录判断
+    pub fn with_home_dir(home_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            bridge: PhotoKitBridge::new(),
+            home_dir: home_dir.into(),
+        }
+    }
+
+    /// 查询 AVCaptureDevice 对指定媒体类型的授权状态
+    fn av_authorization_status(media_type: &str) -> PermissionStatus {
+        let status: i32 = unsafe {
+            let av_media_type = NSString::from_str(media_type);
+            msg_send![
+                class!(AVCaptureDevice),
+                authorizationStatusForMediaType: &*av_media_type
+            ]
+        };
+
+        PermissionStatus::from_av_native_value(status).unwrap_or(PermissionStatus::NotDetermined)
+    }
+
+    /// 确认 Info.plist 中存在请求该权限所需的用途说明
+    ///
+    /// 缺少用途说明时，系统会直接终止进程而不是弹出对话框，因此必须在请求之前检查。
+    fn ensure_usage_description(permission: Permission) -> Result<(), PermissionError> {
+        let Some(key) = permission.usage_description_key() else {
+            return Ok(());
+        };
+
+        let present = unsafe {
+            let bundle: *mut AnyObject = msg_send![class!(NSBundle), mainBundle];
+            let info_key = NSString::from_str(key);
+            let value: *mut AnyObject = msg_send![bundle, objectForInfoDictionaryKey: &*info_key];
+            !value.is_null()
+        };
+
+        if present {
+            Ok(())
+        } else {
+            Err(PermissionError::MissingUsageDescription {
+                permission,
+                key: key.to_string(),
+            })
+        }
+    }
+
+    /// 请求 AVCaptureDevice 对指定媒体类型的访问权限
+    ///
+    /// 完成回调会在系统队列上触发，这里通过通道等待用户的选择，最长等待 `REQUEST_TIMEOUT`。
+    /// 回调只区分允许与拒绝，因此随后重新读取授权状态，保留家长控制或 MDM 造成的受限状态。
+    fn av_request_access(
+        permission: Permission,
+        media_type: &str,
+    ) -> Result<PermissionStatus, PermissionError> {
+        Self::ensure_usage_description(permission)?;
+
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let completion_handler = RcBlock::new(move |granted: Bool| {
+            let _ = sender.try_send(granted.as_bool());
+        });
+
+        unsafe {
+            let av_media_type = NSString::from_str(media_type);
+            let _: () = msg_send![
+                class!(AVCaptureDevice),
+                requestAccessForMediaType: &*av_media_type,
+                completionHandler: &*completion_handler
+            ];
+        }
+
+        let granted = receiver
+            .recv_timeout(REQUEST_TIMEOUT)
+            .map_err(|_| PermissionError::timeout(Some(permission)))?;
+
+        // 系统尚未更新授权状态时以回调结果为准
+        Ok(match Self::av_authorization_status(media_type) {
+            PermissionStatus::NotDetermined => PermissionStatus::from_granted(granted),
+            status => status,
+        })
+    }
+
+    /// 打开系统设置中的指定隐私面板
+    fn open_privacy_pane(anchor: &str) -> Result<(), PermissionError> {
+        Command::new("open")
+            .arg(format!(
+                "x-apple.systempreferences:com.apple.preference.security?{anchor}"
+            ))
+            .output()
+            .map_err(|error| PermissionError::request_failed(error.to_string()))?;
+
+        Ok(())
+    }
+}
+
+impl Default for MacOSPermissionBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PermissionBackend for MacOSPermissionBackend {
+    fn check_accessibility(&self) -> PermissionStatus {
+        PermissionStatus::from_granted(application_is_trusted())
+    }
+
+    fn request_accessibility(&self) {
+        application_is_trusted_with_prompt();
+    }
+
+    fn check_full_disk_access(&self) -> PermissionStatus {
+        // Reference: https://github.com/inket/FullDiskAccess/blob/846e04ea2b84fce843f47d7e7f3421189221829c/Sources/FullDiskAccess/FullDiskAccess.swift#L46
+        let check_dirs = ["Library/Containers/com.apple.stocks", "Library/Safari"];
+
+        let granted = check_dirs
+            .iter()
+            .any(|check_dir| read_dir(self.home_dir.join(check_dir)).is_ok());
+
+        PermissionStatus::from_granted(granted)
+    }
+
+    fn request_full_disk_access(&self) -> Result<(), PermissionError> {
+        Self::open_privacy_pane("Privacy_AllFiles")
+    }
+
+    fn check_screen_recording(&self) -> PermissionStatus {
+        PermissionStatus::from_granted(unsafe { CGPreflightScreenCaptureAccess() })
+    }
+
+    fn request_screen_recording(&self) {
+        unsafe {
+            CGRequestScreenCaptureAccess();
+        }
+    }
+
+    fn check_microphone(&self) -> PermissionStatus {
+        Self::av_authorization_status("soun")
+    }
+
+    fn request_microphone(&self) -> Result<PermissionStatus, PermissionError> {
+        Self::av_request_access(Permission::Microphone, "soun")
+    }
+
+    fn check_camera(&self) -> PermissionStatus {
+        Self::av_authorization_status("vide")
+    }
+
+    fn request_camera(&self) -> Result<PermissionStatus, PermissionError> {
+        Self::av_request_access(Permission::Camera, "vide")
+    }
+
+    fn check_input_monitoring(&self) -> PermissionStatus {
+        // kIOHIDRequestTypeListenEvent = 1
+        PermissionStatus::from_iohid_access(unsafe { IOHIDCheckAccess(1) })
+    }
+
+    fn request_input_monitoring(&self) -> Result<(), PermissionError> {
+        Self::open_privacy_pane("Privacy_ListenEvent")
+    }
+
+    fn check_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        self.bridge
+            .check_authorization_status(access_level)
+            .map_err(PhotoKitManagerError::from)
+    }
+
+    fn request_photokit(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        Self::ensure_usage_description(Permission::PhotoKit(access_level))?;
+
+        self.bridge
+            .request_authorization(access_level)
+            .map_err(PhotoKitManagerError::from)
+    }
+
+    fn is_photokit_available(&self) -> bool {
+        self.bridge.is_framework_available()
+    }
+
+    fn get_photos_count(&self) -> Result<u64, PhotoKitManagerError> {
+        self.bridge
+            .get_photos_count()
+            .map_err(PhotoKitManagerError::from)
+    }
+}
@@ -3,8 +3,10 @@
 //! 此模块提供与 macOS PhotoKit 框架的 Objective-C 互操作功能。
 //! 实现了权限状态检查和权限请求的底层桥接代码。
 
-use crate::{PhotoKitAccessLevel, PhotoKitAuthorizationStatus};
+use crate::{PhotoKitAccessLevel, PhotoKitAuthorizationStatus, REQUEST_TIMEOUT};
+use block2::RcBlock;
 use objc2::{class, msg_send};
+use std::sync::mpsc;
 
 /// PhotoKit 权限桥接错误类型
 #[derive(Debug, thiserror::Error)]
@@ -76,19 +78,18 @@ impl PhotoKitBridge {
 
     /// 请求指定权限级别的授权
     ///
-    /// 此方法会触发系统权限对话框。
-    ///
-    /// 重要发现：PhotoKit的API可能与AVFoundation不同！
-    /// 让我们尝试使用更直接的方法，参考Apple官方文档。
+    /// 此方法会触发系统权限对话框，并阻塞到用户作出选择。
+    /// 如果用户已经作出过选择，系统会立即以当前状态调用完成回调。
     ///
     /// # Arguments
     /// * `access_level` - 要请求的权限级别
     ///
     /// # Returns
-    /// 返回权限请求触发后的状态
+    /// 返回用户选择后的授权状态
     ///
     /// # Errors
-    /// 如果 PhotoKit 框架不可用，返回错误
+    /// 如果 PhotoKit 框架不可用，返回错误；
+    /// 如果在 `REQUEST_TIMEOUT` 内没有收到完成回调，返回 `RequestTimeout`
     pub fn request_authorization(
         &self,
         access_level: PhotoKitAccessLevel,
@@ -99,28 +100,29 @@ impl PhotoKitBridge {
             let ph_photo_library_class = class!(PHPhotoLibrary);
             let access_level_value = access_level.to_native_value();
 
-            // 使用标准的PhotoKit权限请求API
-            type CompletionBlock = Option<extern "C" fn(i32)>;
-            let completion_block: CompletionBlock = None;
+            // 完成回调在任意队列上触发，通过通道把用户选择的状态传回调用线程
+            let (sender, receiver) = mpsc::sync_channel(1);
+            let handler = RcBlock::new(move |status: isize| {
+                let _ = sender.try_send(status as i32);
+            });
 
             // 调用PhotoKit权限请求API
             let _: () = msg_send![
                 ph_photo_library_class,
                 requestAuthorizationForAccessLevel: access_level_value,
-                handler: completion_block
+                handler: &*handler
             ];
 
-            // 立即检查当前状态
-            self.check_authorization_status(access_level)
+            receiver
         });
 
-        match result {
-            Ok(status_result) => status_result,
-            Err(_) => {
-                // 如果调用失败（比如在测试环境中），返回 NotDetermined
-                Ok(PhotoKitAuthorizationStatus::NotDetermined)
-            }
-        }
+        let receiver = result.map_err(|_| PhotoKitBridgeError::FrameworkUnavailable)?;
+        let status = receiver
+            .recv_timeout(REQUEST_TIMEOUT)
+            .map_err(|_| PhotoKitBridgeError::RequestTimeout)?;
+
+        PhotoKitAuthorizationStatus::from_native_value(status)
+            .ok_or(PhotoKitBridgeError::InvalidAuthorizationStatus(status))
     }
 
     /// 检查 PhotoKit 框架是否可用
@@ -1,12 +1,22 @@
 //! PhotoKit 权限状态监听系统
 //!
-//! 此模块提供 PhotoKit 权限状态变化的监听功能，基于 NSNotificationCenter
-//! 实现权限状态变化的实时监听和事件分发。
+//! 此模块提供权限状态变化的监听功能。注册第一个监听器时启动 `ChangeDetector`
+//! 在后台轮询被监听的权限，并在状态变化时把事件分发到前端。
+//!
+//! 每次变化都会发送通用的 `permission-changed` 事件，PhotoKit 权限的变化还会额外发送
+//! `photokit-permission-changed` 事件以兼容旧版本。
 
-use crate::{PermissionStatusChangeEvent, PhotoKitAccessLevel, PhotoKitAuthorizationStatus};
-use std::collections::HashMap;
+use crate::{
+    ChangeCause, ChangeDetector, Config, Permission, PermissionChangedEvent, PermissionStatus,
+    PhotoKitAccessLevel, PhotoKitAuthorizationStatus, PhotoKitPermissionManager, Scheduler,
+    SchedulerStats, SharedClock, SnapshotStore, SystemClock, PROBE_TIMEOUT,
+};
+use std::collections::{hash_map::Entry, BTreeSet, HashMap, HashSet, VecDeque};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, Runtime};
+use std::time::Duration;
+use tauri::{ipc::Channel, AppHandle, Emitter, Manager, Runtime};
 use uuid::Uuid;
 
 /// 监听器错误类型
@@ -24,35 +34,298 @@ pub enum PhotoKitListenerError {
     PlatformNotSupported,
 }
 
+/// 权限变化事件的发送目标
+///
+/// 序列化为 `{ "webview": "main" }`、`{ "labels": ["main", "settings"] }`、`{ "channel": 7 }`、
+/// `"callback"` 或 `"broadcast"`。
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum EmitTarget {
+    /// 发送到注册监听器的 webview
+    #[serde(rename = "webview")]
+    Webview(String),
+
+    /// 发送到指定标签的窗口或 webview
+    #[serde(rename = "labels")]
+    Labels(Vec<String>),
+
+    /// 通过 `watch_permission` 传入的 IPC 通道发送，值为通道 ID
+    #[serde(rename = "channel")]
+    Channel(u32),
+
+    /// 通过 `on_change` 注册的 Rust 回调
+    #[serde(rename = "callback")]
+    Callback,
+
+    /// 广播到所有窗口
+    #[serde(rename = "broadcast")]
+    Broadcast,
+}
+
+impl EmitTarget {
+    /// 根据窗口标签列表创建发送目标，列表为空时广播到所有窗口
+    pub fn from_labels(labels: Vec<String>) -> Self {
+        if labels.is_empty() {
+            EmitTarget::Broadcast
+        } else {
+            EmitTarget::Labels(labels)
+        }
+    }
+
+    /// 获取发送目标中的窗口标签，广播或通道时为空
+    pub fn labels(&self) -> &[String] {
+        match self {
+            EmitTarget::Webview(label) => std::slice::from_ref(label),
+            EmitTarget::Labels(labels) => labels,
+            EmitTarget::Channel(_) | EmitTarget::Callback | EmitTarget::Broadcast => &[],
+        }
+    }
+}
+
+/// 注册监听器时选择的事件发送目标
+///
+/// 与 `EmitTarget` 相同，但 `caller` 表示发起注册的 webview，注册时会解析为它的标签。
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub enum ListenerTarget {
+    /// 发送到发起注册的 webview
+    #[serde(rename = "caller")]
+    Caller,
+
+    /// 发送到指定标签的窗口或 webview
+    #[serde(rename = "labels")]
+    Labels(Vec<String>),
+
+    /// 广播到所有窗口
+    #[serde(rename = "broadcast")]
+    Broadcast,
+}
+
+impl ListenerTarget {
+    /// 解析为具体的发送目标
+    ///
+    /// # Arguments
+    /// * `caller_label` - 发起注册的 webview 的标签
+    pub fn resolve(self, caller_label: &str) -> EmitTarget {
+        match self {
+            ListenerTarget::Caller => EmitTarget::Webview(caller_label.to_string()),
+            ListenerTarget::Labels(labels) => EmitTarget::Labels(labels),
+            ListenerTarget::Broadcast => EmitTarget::Broadcast,
+        }
+    }
+}
+
+/// 监听器关心的状态变化
+///
+/// 序列化后的形式为 `"onGrant"`、`"onRevoke"`、`"onAnyChange"` 或 `{ "statuses": [...] }`。
+/// 已授予指 `authorized` 或 `limited`。
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TransitionFilter {
+    /// 任何状态变化
+    #[default]
+    #[serde(rename = "onAnyChange")]
+    OnAnyChange,
+
+    /// 从未授予变为已授予
+    #[serde(rename = "onGrant")]
+    OnGrant,
+
+    /// 从已授予变为未授予
+    #[serde(rename = "onRevoke")]
+    OnRevoke,
+
+    /// 变为列出的任一状态
+    #[serde(rename = "statuses")]
+    Statuses(Vec<PermissionStatus>),
+}
+
+impl TransitionFilter {
+    /// 判断状态变化是否符合过滤条件
+    ///
+    /// 注册时补发的当前状态没有变化前的状态，此时按从未授予变化处理。
+    ///
+    /// # Arguments
+    /// * `previous_status` - 变化前的状态
+    /// * `status` - 新的状态
+    pub fn matches(
+        &self,
+        previous_status: Option<PermissionStatus>,
+        status: PermissionStatus,
+    ) -> bool {
+        let was_granted = previous_status.is_some_and(PermissionStatus::is_granted);
+        match self {
+            TransitionFilter::OnAnyChange => true,
+            TransitionFilter::OnGrant => !was_granted && status.is_granted(),
+            TransitionFilter::OnRevoke => was_granted && !status.is_granted(),
+            TransitionFilter::Statuses(statuses) => statuses.contains(&status),
+        }
+    }
+}
+
 /// 监听器信息
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ListenerInfo {
     /// 监听器唯一标识符
     pub id: String,
-    /// 监听的权限级别
-    pub access_level: PhotoKitAccessLevel,
+    /// 监听的权限
+    pub permission: Permission,
+    /// 监听的 PhotoKit 权限级别，与 `permission` 一致，不是 PhotoKit 权限时为 None
+    ///
+    /// 为兼容旧版本保留，序列化时非 PhotoKit 权限省略此字段。新代码请使用 `permission`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_level: Option<PhotoKitAccessLevel>,
+    /// 权限变化事件的发送目标
+    pub target: EmitTarget,
     /// 创建时间戳
     pub created_at: u64,
-    /// 是否活跃
+    /// 是否活跃，暂停的监听器不接收变化事件
     pub active: bool,
+    /// 注册此监听器的 webview 标签，webview 销毁或重新加载时移除监听器
+    pub owner: Option<String>,
+    /// 监听器关心的状态变化，不符合的变化不会发送给此监听器
+    pub filter: TransitionFilter,
 }
 
 impl ListenerInfo {
-    /// 创建新的监听器信息
+    /// 创建新的 PhotoKit 监听器信息
+    ///
+    /// 与旧版本相同，事件发送到 `main` 窗口。
+    ///
+    /// # Arguments
+    /// * `access_level` - 要监听的 PhotoKit 权限级别
     pub fn new(access_level: PhotoKitAccessLevel) -> Self {
-        Self {
-            id: Uuid::new_v4().to_string(),
+        Self::new_with_target(
             access_level,
+            EmitTarget::from_labels(Config::default().event_targets),
+        )
+    }
+
+    /// 创建将事件发送到指定目标的监听器信息
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `target` - 权限变化事件的发送目标
+    pub fn new_with_target(permission: impl Into<Permission>, target: EmitTarget) -> Self {
+        let permission = permission.into();
+        let mut info = Self {
+            id: Uuid::new_v4().to_string(),
+            permission,
+            access_level: None,
+            target,
             created_at: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
             active: true,
+            owner: None,
+            filter: TransitionFilter::default(),
+        };
+        info.access_level = info.access_level();
+        info
+    }
+
+    /// 设置监听器关心的状态变化
+    ///
+    /// # Arguments
+    /// * `filter` - 过滤条件
+    pub fn with_filter(mut self, filter: TransitionFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 设置注册此监听器的 webview
+    ///
+    /// # Arguments
+    /// * `webview_label` - 注册此监听器的 webview 标签
+    pub fn with_owner(mut self, webview_label: impl Into<String>) -> Self {
+        self.owner = Some(webview_label.into());
+        self
+    }
+
+    /// 获取监听的 PhotoKit 权限级别，不是 PhotoKit 权限时返回 None
+    pub fn access_level(&self) -> Option<PhotoKitAccessLevel> {
+        match self.permission {
+            Permission::PhotoKit(access_level) => Some(access_level),
+            _ => None,
+        }
+    }
+
+    /// 只有此监听器会收到事件的发送目标
+    ///
+    /// 窗口标签和广播由其他监听器共享，此时改为发送到注册它的 webview；
+    /// 没有注册者时返回 None。
+    fn private_target(&self) -> Option<EmitTarget> {
+        match &self.target {
+            EmitTarget::Webview(_) | EmitTarget::Channel(_) | EmitTarget::Callback => {
+                Some(self.target.clone())
+            }
+            EmitTarget::Labels(_) | EmitTarget::Broadcast => {
+                self.owner.clone().map(EmitTarget::Webview)
+            }
+        }
+    }
+}
+
+/// Rust 侧的权限变化回调
+pub type PermissionChangeCallback = Arc<dyn Fn(&PermissionChangedEvent) + Send + Sync>;
+
+/// 不经过前端事件、直接接收权限变化的订阅者
+#[derive(Clone)]
+enum Subscriber {
+    /// 通过 IPC 通道发送到创建通道的 webview
+    Channel(Channel<PermissionChangedEvent>),
+    /// 在 Rust 侧调用的回调
+    Callback(PermissionChangeCallback),
+}
+
+impl Subscriber {
+    /// 把事件交给订阅者，返回订阅者是否仍然可用
+    ///
+    /// 回调在检测线程上调用，panic 的回调视为不可用并被取消订阅，不会让检测线程退出。
+    fn notify(&self, event: &PermissionChangedEvent) -> bool {
+        match self {
+            Subscriber::Channel(channel) => channel.send(event.clone()).is_ok(),
+            Subscriber::Callback(callback) => {
+                std::panic::catch_unwind(AssertUnwindSafe(|| callback(event))).is_ok()
+            }
         }
     }
 }
 
-/// PhotoKit 权限状态监听器管理器
+/// 防抖窗口内尚未发送的状态变化
+#[derive(Debug, Clone, Copy)]
+struct PendingChange {
+    /// 第一次变化之前的状态
+    previous_status: PermissionStatus,
+    /// 最近一次观察到的状态
+    status: PermissionStatus,
+    /// 最近一次变化的原因
+    cause: ChangeCause,
+    /// 最近一次变化的时间（Unix 时间戳，毫秒）
+    changed_at: u64,
+}
+
+/// Rust 侧权限变化订阅的守卫
+///
+/// 守卫被丢弃时自动取消订阅。
+#[must_use = "丢弃守卫会立即取消订阅"]
+pub struct PermissionChangeGuard<R: Runtime> {
+    listener: PhotoKitPermissionListener<R>,
+    listener_id: String,
+}
+
+impl<R: Runtime> PermissionChangeGuard<R> {
+    /// 订阅对应的监听器标识符
+    pub fn id(&self) -> &str {
+        &self.listener_id
+    }
+}
+
+impl<R: Runtime> Drop for PermissionChangeGuard<R> {
+    fn drop(&mut self) {
+        let _ = self.listener.unregister_listener(&self.listener_id);
+    }
+}
+
+/// 权限状态监听器管理器
 ///
 /// 管理权限状态变化的监听器，提供注册、注销和事件分发功能。
 pub struct PhotoKitPermissionListener<R: Runtime> {
@@ -60,8 +333,57 @@ pub struct PhotoKitPermissionListener<R: Runtime> {
     app_handle: AppHandle<R>,
     /// 活跃的监听器映射
     listeners: Arc<Mutex<HashMap<String, ListenerInfo>>>,
-    /// 是否已初始化 NSNotificationCenter 监听
-    notification_initialized: Arc<Mutex<bool>>,
+    /// 通道和回调订阅，键为监听器标识符
+    subscribers: Arc<Mutex<HashMap<String, Subscriber>>>,
+    /// 每个被监听权限上一次观察到的状态
+    last_seen: Arc<Mutex<HashMap<Permission, PermissionStatus>>>,
+    /// 防抖窗口内尚未发送的状态变化
+    pending: Arc<Mutex<HashMap<Permission, PendingChange>>>,
+    /// 上一个事件的序号
+    sequence: Arc<AtomicU64>,
+    /// 最近分发的变化事件，最旧的在前
+    history: Arc<Mutex<VecDeque<PermissionChangedEvent>>>,
+    /// 最多保留的历史事件数量，为 0 时不记录
+    history_capacity: usize,
+    /// 启动时与上次运行比较发现的变化，补发给之后注册的监听器
+    reconciled: Arc<Mutex<HashMap<Permission, PermissionChangedEvent>>>,
+    /// 所有被监听权限共用的检查调度器
+    scheduler: Arc<Scheduler>,
+    /// 注册时未指定发送目标的监听器使用的发送目标
+    default_target: EmitTarget,
+    /// 检测权限变化的基础轮询间隔，为 None 时不启动后台检测
+    polling_interval: Option<Duration>,
+    /// 状态没有变化时轮询间隔翻倍的上限，为 None 时以固定间隔轮询
+    max_polling_interval: Option<Duration>,
+    /// 状态稳定多久后才发送变化事件，为零时立即发送
+    debounce_window: Duration,
+    /// 判断防抖窗口和轮询间隔是否到期的时钟
+    clock: SharedClock,
+    /// 观察到的状态写入的快照存储，为 None 时不保存
+    snapshot_store: Option<SnapshotStore>,
+}
+
+impl<R: Runtime> Clone for PhotoKitPermissionListener<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: self.app_handle.clone(),
+            listeners: self.listeners.clone(),
+            subscribers: self.subscribers.clone(),
+            last_seen: self.last_seen.clone(),
+            pending: self.pending.clone(),
+            sequence: self.sequence.clone(),
+            history: self.history.clone(),
+            history_capacity: self.history_capacity,
+            reconciled: self.reconciled.clone(),
+            scheduler: self.scheduler.clone(),
+            default_target: self.default_target.clone(),
+            polling_interval: self.polling_interval,
+            max_polling_interval: self.max_polling_interval,
+            debounce_window: self.debounce_window,
+            clock: self.clock.clone(),
+            snapshot_store: self.snapshot_store.clone(),
+        }
+    }
 }
 
 impl<R: Runtime> PhotoKitPermissionListener<R> {
@@ -70,14 +392,96 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
     /// # Arguments
     /// * `app_handle` - Tauri 应用句柄
     pub fn new(app_handle: AppHandle<R>) -> Self {
+        Self::with_event_targets(app_handle, Config::default().event_targets)
+    }
+
+    /// 创建将事件发送到指定窗口的权限监听器管理器
+    ///
+    /// # Arguments
+    /// * `app_handle` - Tauri 应用句柄
+    /// * `event_targets` - 接收权限变化事件的窗口标签，为空时广播到所有窗口
+    pub fn with_event_targets(app_handle: AppHandle<R>, event_targets: Vec<String>) -> Self {
         Self {
             app_handle,
             listeners: Arc::new(Mutex::new(HashMap::new())),
-            notification_initialized: Arc::new(Mutex::new(false)),
+            subscribers: Arc::new(Mutex::new(HashMap::new())),
+            last_seen: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            sequence: Arc::new(AtomicU64::new(0)),
+            history: Arc::new(Mutex::new(VecDeque::new())),
+            history_capacity: Config::default().history_capacity,
+            reconciled: Arc::new(Mutex::new(HashMap::new())),
+            scheduler: Arc::new(Scheduler::default()),
+            default_target: EmitTarget::from_labels(event_targets),
+            polling_interval: None,
+            max_polling_interval: None,
+            debounce_window: Duration::ZERO,
+            clock: Arc::new(SystemClock),
+            snapshot_store: None,
         }
     }
 
-    /// 注册权限状态监听器
+    /// 设置检测权限变化的轮询间隔
+    ///
+    /// # Arguments
+    /// * `polling_interval` - 两次检查之间的间隔，为 None 时不启动后台检测
+    pub fn with_polling_interval(mut self, polling_interval: Option<Duration>) -> Self {
+        self.polling_interval = polling_interval;
+        self
+    }
+
+    /// 设置状态没有变化时轮询间隔翻倍的上限
+    ///
+    /// # Arguments
+    /// * `max_polling_interval` - 最长轮询间隔，为 None 时以固定间隔轮询
+    pub fn with_max_polling_interval(mut self, max_polling_interval: Option<Duration>) -> Self {
+        self.max_polling_interval = max_polling_interval;
+        self
+    }
+
+    /// 设置防抖窗口
+    ///
+    /// 状态变化后在窗口内保持不变才发送事件，窗口内的连续变化合并为一个事件，
+    /// 事件中保留第一次变化之前的状态。变回原来的状态时不发送事件。
+    /// 等待中的变化由后台检测发送，因此没有设置轮询间隔时不防抖。
+    ///
+    /// # Arguments
+    /// * `debounce_window` - 状态需要保持不变的时长，为零时立即发送
+    pub fn with_debounce_window(mut self, debounce_window: Duration) -> Self {
+        self.debounce_window = debounce_window;
+        self
+    }
+
+    /// 设置最多保留的历史事件数量
+    ///
+    /// # Arguments
+    /// * `history_capacity` - 历史事件数量上限，超出时丢弃最旧的事件，为 0 时不记录
+    pub fn with_history_capacity(mut self, history_capacity: usize) -> Self {
+        self.history_capacity = history_capacity;
+        self
+    }
+
+    /// 设置判断防抖窗口和轮询间隔是否到期、为事件打时间戳的时钟，测试中可传入 `ManualClock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 设置保存观察到的权限状态的快照存储
+    ///
+    /// # Arguments
+    /// * `snapshot_store` - 插件共享的快照存储
+    pub fn with_snapshot_store(mut self, snapshot_store: SnapshotStore) -> Self {
+        self.snapshot_store = Some(snapshot_store);
+        self
+    }
+
+    /// 注册时未指定发送目标的监听器使用的发送目标
+    pub fn default_target(&self) -> &EmitTarget {
+        &self.default_target
+    }
+
+    /// 注册 PhotoKit 权限状态监听器
     ///
     /// # Arguments
     /// * `access_level` - 要监听的权限级别
@@ -91,7 +495,79 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
         &self,
         access_level: PhotoKitAccessLevel,
     ) -> Result<String, PhotoKitListenerError> {
-        let listener_info = ListenerInfo::new(access_level);
+        self.register_permission_listener(Permission::PhotoKit(access_level))
+    }
+
+    /// 注册权限状态监听器
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    ///
+    /// # Returns
+    /// 返回监听器的唯一标识符
+    ///
+    /// # Errors
+    /// 如果监听器注册失败，返回相应的错误
+    pub fn register_permission_listener(
+        &self,
+        permission: Permission,
+    ) -> Result<String, PhotoKitListenerError> {
+        self.register_permission_listener_with_target(permission, self.default_target.clone())
+    }
+
+    /// 注册将事件发送到指定目标的权限状态监听器
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `target` - 权限变化事件的发送目标
+    ///
+    /// # Returns
+    /// 返回监听器的唯一标识符
+    ///
+    /// # Errors
+    /// 如果监听器注册失败，返回相应的错误
+    pub fn register_permission_listener_with_target(
+        &self,
+        permission: Permission,
+        target: EmitTarget,
+    ) -> Result<String, PhotoKitListenerError> {
+        self.insert_listener(ListenerInfo::new_with_target(permission, target))
+    }
+
+    /// 注册属于指定 webview 的权限状态监听器
+    ///
+    /// 监听器在 webview 销毁或重新加载时自动移除。
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `target` - 权限变化事件的发送目标
+    /// * `webview_label` - 注册此监听器的 webview 标签
+    /// * `filter` - 监听器关心的状态变化
+    ///
+    /// # Returns
+    /// 返回监听器的唯一标识符
+    ///
+    /// # Errors
+    /// 如果监听器注册失败，返回相应的错误
+    pub fn register_webview_listener(
+        &self,
+        permission: Permission,
+        target: EmitTarget,
+        webview_label: impl Into<String>,
+        filter: TransitionFilter,
+    ) -> Result<String, PhotoKitListenerError> {
+        self.insert_listener(
+            ListenerInfo::new_with_target(permission, target)
+                .with_owner(webview_label)
+                .with_filter(filter),
+        )
+    }
+
+    /// 把监听器加入注册表并启动后台变化检测
+    fn insert_listener(
+        &self,
+        listener_info: ListenerInfo,
+    ) -> Result<String, PhotoKitListenerError> {
         let listener_id = listener_info.id.clone();
 
         // 添加监听器到映射中
@@ -101,15 +577,141 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
                 .lock()
                 .map_err(|_| PhotoKitListenerError::LockFailed)?;
 
-            listeners.insert(listener_id.clone(), listener_info);
+            listeners.insert(listener_id.clone(), listener_info.clone());
         }
 
-        // 初始化 NSNotificationCenter 监听（如果尚未初始化）
+        // 启动后台变化检测（如果尚未启动）
+        self.initialize_notification_listener()?;
+        self.replay_current_status(&listener_info)?;
+
+        Ok(listener_id)
+    }
+
+    /// 通过 IPC 通道订阅权限变化
+    ///
+    /// 通道发送失败或创建通道的 webview 销毁、重新加载时自动取消订阅。
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `channel` - 接收 `PermissionChangedEvent` 的通道
+    /// * `webview_label` - 创建通道的 webview 标签
+    /// * `filter` - 订阅关心的状态变化
+    ///
+    /// # Returns
+    /// 返回监听器的唯一标识符，可以传给 `unregister_listener` 提前取消订阅
+    pub fn watch_permission(
+        &self,
+        permission: Permission,
+        channel: Channel<PermissionChangedEvent>,
+        webview_label: String,
+        filter: TransitionFilter,
+    ) -> Result<String, PhotoKitListenerError> {
+        let listener_info =
+            ListenerInfo::new_with_target(permission, EmitTarget::Channel(channel.id()))
+                .with_owner(webview_label)
+                .with_filter(filter);
+        self.subscribe(listener_info, Subscriber::Channel(channel))
+    }
+
+    /// 在 Rust 侧订阅权限变化
+    ///
+    /// 回调由与前端事件相同的变化检测驱动，在检测线程上调用。
+    /// 订阅时先以 `initial` 为原因收到一次当前状态。
+    ///
+    /// # Arguments
+    /// * `permission` - 要监听的权限
+    /// * `callback` - 每次状态变化时调用的回调
+    ///
+    /// # Returns
+    /// 返回订阅守卫，守卫被丢弃时取消订阅
+    pub fn on_change<F>(
+        &self,
+        permission: Permission,
+        callback: F,
+    ) -> Result<PermissionChangeGuard<R>, PhotoKitListenerError>
+    where
+        F: Fn(&PermissionChangedEvent) + Send + Sync + 'static,
+    {
+        let listener_id = self.subscribe(
+            ListenerInfo::new_with_target(permission, EmitTarget::Callback),
+            Subscriber::Callback(Arc::new(callback)),
+        )?;
+
+        Ok(PermissionChangeGuard {
+            listener: self.clone(),
+            listener_id,
+        })
+    }
+
+    /// 注册带有订阅者的监听器
+    fn subscribe(
+        &self,
+        listener_info: ListenerInfo,
+        subscriber: Subscriber,
+    ) -> Result<String, PhotoKitListenerError> {
+        let listener_id = listener_info.id.clone();
+
+        // 先保存订阅者，保证监听器生效时事件能找到订阅者
+        self.subscribers
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .insert(listener_id.clone(), subscriber);
+
+        self.listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .insert(listener_id.clone(), listener_info.clone());
+
         self.initialize_notification_listener()?;
+        self.replay_current_status(&listener_info)?;
 
         Ok(listener_id)
     }
 
+    /// 移除指定 webview 注册的所有监听器和通道订阅
+    ///
+    /// 在 webview 所在窗口销毁或页面重新加载时调用。
+    ///
+    /// # Arguments
+    /// * `webview_label` - 被销毁或重新加载的 webview 标签
+    pub fn remove_webview_listeners(
+        &self,
+        webview_label: &str,
+    ) -> Result<(), PhotoKitListenerError> {
+        let removed: Vec<String> = self
+            .listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .values()
+            .filter(|info| info.owner.as_deref() == Some(webview_label))
+            .map(|info| info.id.clone())
+            .collect();
+
+        self.remove_listeners(&removed)
+    }
+
+    /// 从注册表中移除指定的监听器及其订阅者
+    fn remove_listeners(&self, listener_ids: &[String]) -> Result<(), PhotoKitListenerError> {
+        if listener_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut listeners = self
+            .listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        for id in listener_ids {
+            listeners.remove(id);
+            subscribers.remove(id);
+        }
+        Ok(())
+    }
+
     /// 注销权限状态监听器
     ///
     /// # Arguments
@@ -129,9 +731,72 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
             ));
         }
 
+        self.subscribers
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .remove(listener_id);
+
+        Ok(())
+    }
+
+    /// 暂停监听器
+    ///
+    /// 暂停期间的状态变化不会发送给此监听器，恢复后也不会补发。
+    ///
+    /// # Arguments
+    /// * `listener_id` - 要暂停的监听器标识符
+    ///
+    /// # Errors
+    /// 如果监听器不存在，返回 `ListenerNotFound`
+    pub fn pause_listener(&self, listener_id: &str) -> Result<(), PhotoKitListenerError> {
+        self.set_listener_active(listener_id, false)
+    }
+
+    /// 恢复被暂停的监听器
+    ///
+    /// # Arguments
+    /// * `listener_id` - 要恢复的监听器标识符
+    ///
+    /// # Errors
+    /// 如果监听器不存在，返回 `ListenerNotFound`
+    pub fn resume_listener(&self, listener_id: &str) -> Result<(), PhotoKitListenerError> {
+        self.set_listener_active(listener_id, true)?;
+
+        // 所有监听器都暂停时检测线程会退出，恢复时重新启动
+        self.initialize_notification_listener()
+    }
+
+    /// 设置监听器的活跃状态
+    fn set_listener_active(
+        &self,
+        listener_id: &str,
+        active: bool,
+    ) -> Result<(), PhotoKitListenerError> {
+        let mut listeners = self
+            .listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        let info = listeners
+            .get_mut(listener_id)
+            .ok_or_else(|| PhotoKitListenerError::ListenerNotFound(listener_id.to_string()))?;
+        info.active = active;
         Ok(())
     }
 
+    /// 获取所有监听器，包括被暂停的监听器
+    ///
+    /// # Returns
+    /// 返回所有监听器的信息列表，暂停的监听器 `active` 为 false
+    pub fn get_all_listeners(&self) -> Result<Vec<ListenerInfo>, PhotoKitListenerError> {
+        let listeners = self
+            .listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        Ok(listeners.values().cloned().collect())
+    }
+
     /// 获取所有活跃的监听器
     ///
     /// # Returns
@@ -157,12 +822,17 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
             .map_err(|_| PhotoKitListenerError::LockFailed)?;
 
         listeners.clear();
+        self.subscribers
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .clear();
         Ok(())
     }
 
-    /// 处理权限状态变化事件
+    /// 处理 PhotoKit 权限状态变化事件
     ///
     /// 当检测到权限状态变化时，此方法会被调用来分发事件到前端。
+    /// 被暂停的监听器不会收到事件。
     ///
     /// # Arguments
     /// * `new_status` - 新的权限状态
@@ -172,96 +842,667 @@ impl<R: Runtime> PhotoKitPermissionListener<R> {
         new_status: PhotoKitAuthorizationStatus,
         access_level: PhotoKitAccessLevel,
     ) -> Result<(), PhotoKitListenerError> {
-        // 检查是否有监听器关注此权限级别
-        let has_listeners = {
-            let listeners = self
-                .listeners
+        self.handle_status_change(Permission::PhotoKit(access_level), new_status.into())
+    }
+
+    /// 处理权限状态变化事件
+    ///
+    /// 无论状态是否与上一次观察到的相同都会分发事件，原因为外部变化。
+    ///
+    /// # Arguments
+    /// * `permission` - 状态发生变化的权限
+    /// * `new_status` - 新的权限状态
+    pub fn handle_status_change(
+        &self,
+        permission: Permission,
+        new_status: PermissionStatus,
+    ) -> Result<(), PhotoKitListenerError> {
+        self.record_snapshot_status(permission, new_status);
+        let previous_status = self
+            .last_seen
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .insert(permission, new_status);
+
+        self.dispatch(
+            permission,
+            previous_status,
+            new_status,
+            ChangeCause::ExternalChange,
+        )
+    }
+
+    /// 记录观察到的权限状态，与上一次观察到的状态不同时分发事件
+    ///
+    /// 没有监听器关注的权限不会被记录；第一次观察到的状态只作为基准，不分发事件。
+    /// 设置了防抖窗口时，变化先记录下来，由 `flush_pending` 在状态稳定后发送。
+    ///
+    /// # Arguments
+    /// * `permission` - 被检查的权限
+    /// * `status` - 观察到的权限状态
+    /// * `cause` - 状态变化的原因
+    ///
+    /// # Returns
+    /// 分发了事件时返回 true
+    pub fn observe_status(
+        &self,
+        permission: Permission,
+        status: PermissionStatus,
+        cause: ChangeCause,
+    ) -> Result<bool, PhotoKitListenerError> {
+        // 没有监听器关注的权限也可能被请求改变，同样写入快照
+        self.record_snapshot_status(permission, status);
+
+        let watched = self
+            .get_active_listeners()?
+            .iter()
+            .any(|info| info.permission == permission);
+
+        let previous_status = {
+            let mut last_seen = self
+                .last_seen
                 .lock()
                 .map_err(|_| PhotoKitListenerError::LockFailed)?;
 
-            listeners
-                .values()
-                .any(|info| info.access_level == access_level && info.active)
+            if !watched {
+                last_seen.remove(&permission);
+                return Ok(false);
+            }
+            last_seen.insert(permission, status)
         };
 
-        if !has_listeners {
-            return Ok(());
+        match previous_status {
+            Some(previous_status) if previous_status != status => {
+                // 没有后台检测时无法在窗口结束后发送，直接分发
+                if !self.debounce_window.is_zero() && self.polling_interval.is_some() {
+                    self.defer_change(permission, previous_status, status, cause)?;
+                    return Ok(false);
+                }
+
+                self.dispatch(permission, Some(previous_status), status, cause)?;
+                Ok(true)
+            }
+            _ => Ok(false),
         }
+    }
 
-        // 创建权限状态变化事件
-        let event = PermissionStatusChangeEvent::new(new_status, access_level);
+    /// 把观察到的状态写入快照存储，保存失败不影响事件分发
+    fn record_snapshot_status(&self, permission: Permission, status: PermissionStatus) {
+        if let Some(snapshot_store) = &self.snapshot_store {
+            let _ = snapshot_store.record_status(permission, status, self.clock.now_millis());
+        }
+    }
 
-        // 发送事件到前端
-        self.app_handle
-            .emit_to("main", "photokit-permission-changed", &event)
-            .map_err(|e| PhotoKitListenerError::EventEmitFailed(e.to_string()))?;
+    /// 把状态变化记录到防抖窗口中，等待状态稳定
+    fn defer_change(
+        &self,
+        permission: Permission,
+        previous_status: PermissionStatus,
+        status: PermissionStatus,
+        cause: ChangeCause,
+    ) -> Result<(), PhotoKitListenerError> {
+        let now = self.clock.now_millis();
+        let mut pending = self
+            .pending
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
 
+        // 检测线程可能正处于退避后的长间隔中，确保窗口结束时检查
+        self.scheduler
+            .poll_by(now.saturating_add(self.debounce_window.as_millis() as u64));
+
+        match pending.entry(permission) {
+            Entry::Occupied(mut entry) => {
+                // 变回第一次变化之前的状态，相当于没有变化
+                if entry.get().previous_status == status {
+                    entry.remove();
+                } else {
+                    let change = entry.get_mut();
+                    change.status = status;
+                    change.cause = cause;
+                    change.changed_at = now;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(PendingChange {
+                    previous_status,
+                    status,
+                    cause,
+                    changed_at: now,
+                });
+            }
+        }
         Ok(())
     }
 
-    /// 初始化 NSNotificationCenter 监听
+    /// 发送在防抖窗口内保持不变的状态变化
     ///
-    /// 在 macOS 上，这会设置 NSNotificationCenter 来监听 PhotoKit 权限变化。
-    /// 在其他平台上，这是一个空操作。
-    fn initialize_notification_listener(&self) -> Result<(), PhotoKitListenerError> {
-        let mut initialized = self
-            .notification_initialized
+    /// 由变化检测在每次检查后调用。
+    ///
+    /// # Returns
+    /// 发送的变化事件数量
+    pub fn flush_pending(&self) -> Result<usize, PhotoKitListenerError> {
+        let now = self.clock.now_millis();
+        let window = self.debounce_window.as_millis() as u64;
+
+        let settled: Vec<(Permission, PendingChange)> = {
+            let mut pending = self
+                .pending
+                .lock()
+                .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+            let permissions: Vec<Permission> = pending
+                .iter()
+                .filter(|(_, change)| now.saturating_sub(change.changed_at) >= window)
+                .map(|(permission, _)| *permission)
+                .collect();
+
+            permissions
+                .into_iter()
+                .filter_map(|permission| {
+                    pending
+                        .remove(&permission)
+                        .map(|change| (permission, change))
+                })
+                .collect()
+        };
+
+        for (permission, change) in &settled {
+            self.dispatch(
+                *permission,
+                Some(change.previous_status),
+                change.status,
+                change.cause,
+            )?;
+        }
+
+        Ok(settled.len())
+    }
+
+    /// 最早一个等待中的变化在防抖窗口结束后可以发送的时间
+    ///
+    /// # Returns
+    /// 没有等待中的变化时返回 None
+    pub(crate) fn pending_deadline(&self) -> Result<Option<u64>, PhotoKitListenerError> {
+        let window = self.debounce_window.as_millis() as u64;
+        let pending = self
+            .pending
             .lock()
             .map_err(|_| PhotoKitListenerError::LockFailed)?;
 
-        if *initialized {
+        Ok(pending
+            .values()
+            .map(|change| change.changed_at.saturating_add(window))
+            .min())
+    }
+
+    /// 丢弃不再被监听的权限的基准状态，重新监听时会重新建立基准
+    pub(crate) fn retain_baselines(
+        &self,
+        watched: &HashSet<Permission>,
+    ) -> Result<(), PhotoKitListenerError> {
+        self.last_seen
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .retain(|permission, _| watched.contains(permission));
+        self.pending
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .retain(|permission, _| watched.contains(permission));
+        Ok(())
+    }
+
+    /// 把权限的当前状态以 `initial` 为原因发送给新注册的监听器
+    ///
+    /// 只发送给这一个监听器，当前状态由 `current_status` 确定。
+    /// 监听器的目标由其他监听器共享时只发送到注册它的 webview，避免已有的监听器收到重复的事件。
+    /// 启动时发现了此权限的变化时，先补发该 `startupReconciliation` 事件。
+    /// 应用中没有注册 `PhotoKitPermissionManager`、检查失败或超时时不发送当前状态。
+    fn replay_current_status(
+        &self,
+        listener_info: &ListenerInfo,
+    ) -> Result<(), PhotoKitListenerError> {
+        let targets: Vec<EmitTarget> = listener_info.private_target().into_iter().collect();
+        let ids = std::slice::from_ref(&listener_info.id);
+
+        let reconciled = self
+            .reconciled
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .get(&listener_info.permission)
+            .cloned();
+        if let Some(event) = reconciled {
+            if listener_info
+                .filter
+                .matches(event.previous_status, event.status)
+            {
+                self.deliver(ids, &targets, &event)?;
+            }
+        }
+
+        let permission = listener_info.permission;
+        let Some(status) = self.current_status(permission)? else {
+            return Ok(());
+        };
+
+        if !listener_info.filter.matches(None, status) {
             return Ok(());
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            // 在实际实现中，这里会设置 NSNotificationCenter 监听
-            // 由于 objc2 的复杂性，这里我们使用一个简化的实现
-            // 实际的 NSNotificationCenter 监听需要更复杂的 Objective-C 桥接代码
+        let event = self.new_event(permission, None, status, ChangeCause::Initial);
+        self.deliver(ids, &targets, &event)
+    }
 
-            // TODO: 实现真正的 NSNotificationCenter 监听
-            // 这需要：
-            // 1. 注册 PHPhotoLibraryAvailabilityDidChangeNotification 通知
-            // 2. 设置回调函数来处理通知
-            // 3. 在回调中调用 handle_permission_change
+    /// 新监听器的当前状态
+    ///
+    /// 已有其他监听器关注此权限时使用它们的基准状态，否则在 `PROBE_TIMEOUT` 内检查并把结果作为新的基准。
+    /// 注册命令不会被慢的检查（例如完全磁盘访问）长时间阻塞。
+    ///
+    /// # Returns
+    /// 没有注册 `PhotoKitPermissionManager`、检查失败或超时时返回 None
+    fn current_status(
+        &self,
+        permission: Permission,
+    ) -> Result<Option<PermissionStatus>, PhotoKitListenerError> {
+        // 没有其他监听器时基准状态可能在检测线程停止后已经过期
+        if self.active_watchers(permission)?.len() > 1 {
+            let last_seen = self
+                .last_seen
+                .lock()
+                .map_err(|_| PhotoKitListenerError::LockFailed)?
+                .get(&permission)
+                .copied();
+            if last_seen.is_some() {
+                return Ok(last_seen);
+            }
         }
 
-        #[cfg(not(target_os = "macos"))]
-        {
-            // 在非 macOS 平台上，我们不需要实际的监听
+        let Some(manager) = self.app_handle.try_state::<PhotoKitPermissionManager>() else {
+            return Ok(None);
+        };
+        let Some(Ok(status)) = manager.refresh_permission_within(permission, PROBE_TIMEOUT) else {
+            return Ok(None);
+        };
+
+        self.last_seen
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .insert(permission, status);
+        Ok(Some(status))
+    }
+
+    /// 把状态变化分发给关注此权限的监听器
+    fn dispatch(
+        &self,
+        permission: Permission,
+        previous_status: Option<PermissionStatus>,
+        status: PermissionStatus,
+        cause: ChangeCause,
+    ) -> Result<(), PhotoKitListenerError> {
+        let watchers = self.active_watchers(permission)?;
+        if watchers.is_empty() {
+            return Ok(());
+        }
+
+        let event = self.new_event(permission, previous_status, status, cause);
+        self.record_history(&event)?;
+
+        // 过滤条件不符合的监听器收不到事件，事件也不会发送到它们的目标
+        let (ids, targets): (Vec<String>, Vec<EmitTarget>) = watchers
+            .into_iter()
+            .filter(|(_, _, filter)| filter.matches(previous_status, status))
+            .map(|(id, target, _)| (id, target))
+            .unzip();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.deliver(&ids, &targets, &event)
+    }
+
+    /// 分发启动时与上次运行保存的快照比较发现的状态变化
+    ///
+    /// 启动时通常还没有注册监听器，没有监听器关注此权限时事件发送到默认目标。
+    /// 有监听器时只发送给过滤条件匹配的监听器。事件始终记录到历史中，
+    /// 并在本次运行中补发给之后注册的此权限的监听器。
+    ///
+    /// # Arguments
+    /// * `permission` - 发生变化的权限
+    /// * `previous_status` - 上次运行保存的状态
+    /// * `status` - 启动时检查到的状态
+    pub fn reconcile_status(
+        &self,
+        permission: Permission,
+        previous_status: PermissionStatus,
+        status: PermissionStatus,
+    ) -> Result<(), PhotoKitListenerError> {
+        let watchers = self.active_watchers(permission)?;
+        let watched = !watchers.is_empty();
+        if watched {
+            self.last_seen
+                .lock()
+                .map_err(|_| PhotoKitListenerError::LockFailed)?
+                .insert(permission, status);
+        }
+
+        let event = self.new_event(
+            permission,
+            Some(previous_status),
+            status,
+            ChangeCause::StartupReconciliation,
+        );
+        self.record_history(&event)?;
+        self.reconciled
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?
+            .insert(permission, event.clone());
+
+        let (ids, mut targets): (Vec<String>, Vec<EmitTarget>) = watchers
+            .into_iter()
+            .filter(|(_, _, filter)| filter.matches(Some(previous_status), status))
+            .map(|(id, target, _)| (id, target))
+            .unzip();
+
+        // 过滤条件拒绝了事件时不再发送到默认目标
+        if !watched {
+            targets.push(self.default_target.clone());
+        }
+        if targets.is_empty() {
+            return Ok(());
+        }
+        self.deliver(&ids, &targets, &event)
+    }
+
+    /// 收集关注此权限且未暂停的监听器
+    fn active_watchers(
+        &self,
+        permission: Permission,
+    ) -> Result<Vec<(String, EmitTarget, TransitionFilter)>, PhotoKitListenerError> {
+        let listeners = self
+            .listeners
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        Ok(listeners
+            .values()
+            .filter(|info| info.permission == permission && info.active)
+            .map(|info| (info.id.clone(), info.target.clone(), info.filter.clone()))
+            .collect())
+    }
+
+    /// 创建带有下一个序号、以监听器时钟为时间戳的事件
+    fn new_event(
+        &self,
+        permission: Permission,
+        previous_status: Option<PermissionStatus>,
+        status: PermissionStatus,
+        cause: ChangeCause,
+    ) -> PermissionChangedEvent {
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut event =
+            PermissionChangedEvent::new(permission, previous_status, status, cause, sequence);
+        event.timestamp = self.clock.now_millis();
+        event
+    }
+
+    /// 把事件加入历史记录，超出容量时丢弃最旧的事件
+    fn record_history(&self, event: &PermissionChangedEvent) -> Result<(), PhotoKitListenerError> {
+        if self.history_capacity == 0 {
+            return Ok(());
+        }
+
+        let mut history = self
+            .history
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        while history.len() >= self.history_capacity {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+        Ok(())
+    }
+
+    /// 查询本次运行中分发过的权限变化事件
+    ///
+    /// 只保留最近的 `history_capacity` 个事件，注册时补发的 `initial` 事件不会记录。
+    ///
+    /// # Arguments
+    /// * `since` - 只返回时间戳不早于此时间的事件（Unix 时间戳，毫秒）
+    /// * `permission` - 只返回此权限的事件，为 None 时返回所有权限的事件
+    ///
+    /// # Returns
+    /// 按发生顺序排列的事件列表
+    pub fn event_history(
+        &self,
+        since: u64,
+        permission: Option<Permission>,
+    ) -> Result<Vec<PermissionChangedEvent>, PhotoKitListenerError> {
+        let history = self
+            .history
+            .lock()
+            .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+        Ok(history
+            .iter()
+            .filter(|event| event.timestamp >= since)
+            .filter(|event| permission.map_or(true, |permission| event.permission == permission))
+            .cloned()
+            .collect())
+    }
+
+    /// 把事件交给监听器的订阅者并发送到它们的目标
+    ///
+    /// 发送 `permission-changed` 事件，PhotoKit 权限还会发送 `photokit-permission-changed` 事件。
+    fn deliver(
+        &self,
+        listener_ids: &[String],
+        targets: &[EmitTarget],
+        event: &PermissionChangedEvent,
+    ) -> Result<(), PhotoKitListenerError> {
+        self.notify_subscribers(listener_ids, event)?;
+        self.emit("permission-changed", event, targets)?;
+
+        if let Some(photokit_event) = event.to_photokit_event() {
+            self.emit("photokit-permission-changed", &photokit_event, targets)?;
         }
 
-        *initialized = true;
         Ok(())
     }
-}
 
-// 线程安全标记
-unsafe impl<R: Runtime> Send for PhotoKitPermissionListener<R> {}
-unsafe impl<R: Runtime> Sync for PhotoKitPermissionListener<R> {}
+    /// 把事件交给监听器的订阅者，发送失败的通道视为已关闭并取消订阅
+    ///
+    /// 回调在锁外调用，因此回调中可以注册或注销监听器。
+    fn notify_subscribers(
+        &self,
+        listener_ids: &[String],
+        event: &PermissionChangedEvent,
+    ) -> Result<(), PhotoKitListenerError> {
+        let subscribers: Vec<(String, Subscriber)> = {
+            let subscribers = self
+                .subscribers
+                .lock()
+                .map_err(|_| PhotoKitListenerError::LockFailed)?;
+
+            listener_ids
+                .iter()
+                .filter_map(|id| {
+                    subscribers
+                        .get(id)
+                        .map(|subscriber| (id.clone(), subscriber.clone()))
+                })
+                .collect()
+        };
+
+        let closed: Vec<String> = subscribers
+            .into_iter()
+            .filter(|(_, subscriber)| !subscriber.notify(event))
+            .map(|(id, _)| id)
+            .collect();
+
+        self.remove_listeners(&closed)
+    }
+
+    /// 把事件发送到监听器的目标
+    ///
+    /// 任一监听器要求广播时只广播一次，否则每个窗口标签只发送一次。
+    fn emit<S: serde::Serialize + Clone>(
+        &self,
+        event: &str,
+        payload: &S,
+        targets: &[EmitTarget],
+    ) -> Result<(), PhotoKitListenerError> {
+        if targets.contains(&EmitTarget::Broadcast) {
+            return self
+                .app_handle
+                .emit(event, payload)
+                .map_err(|e| PhotoKitListenerError::EventEmitFailed(e.to_string()));
+        }
+
+        let labels: BTreeSet<&str> = targets
+            .iter()
+            .flat_map(EmitTarget::labels)
+            .map(String::as_str)
+            .collect();
+
+        for label in labels {
+            self.app_handle
+                .emit_to(label, event, payload)
+                .map_err(|e| PhotoKitListenerError::EventEmitFailed(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// 启动后台变化检测
+    ///
+    /// 只有在设置了轮询间隔并且应用中已注册 `PhotoKitPermissionManager` 时才会启动。
+    /// PhotoKit 没有授权状态变化的系统通知，因此在所有平台上都通过轮询检测变化。
+    fn initialize_notification_listener(&self) -> Result<(), PhotoKitListenerError> {
+        let Some(polling_interval) = self.polling_interval else {
+            return Ok(());
+        };
+        let Some(manager) = self.app_handle.try_state::<PhotoKitPermissionManager>() else {
+            return Ok(());
+        };
+
+        // 检测线程已在运行时只唤醒它尽快检查新监听的权限
+        if !self.scheduler.start()? {
+            return Ok(());
+        }
+
+        let detector = ChangeDetector::new(
+            manager.inner().clone(),
+            self.clone(),
+            self.clock.clone(),
+            polling_interval,
+        )
+        .with_max_interval(self.max_polling_interval.unwrap_or(polling_interval));
+
+        if let Err(e) = detector.spawn() {
+            self.scheduler.stop_if(|| true);
+            return Err(PhotoKitListenerError::EventEmitFailed(e.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 如果没有活跃的监听器，标记后台检测已停止
+    ///
+    /// 由检测线程调用。
+    ///
+    /// # Returns
+    /// 检测线程应当退出时返回 true
+    pub(crate) fn release_detector_if_idle(&self) -> bool {
+        self.scheduler.stop_if(|| {
+            self.get_active_listeners()
+                .map_or(true, |listeners| listeners.is_empty())
+        })
+    }
+
+    /// 所有被监听权限共用的检查调度器
+    pub(crate) fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    /// 恢复基础轮询间隔并让检测线程立即检查
+    ///
+    /// 在发起权限请求或应用获得焦点后调用，此时权限状态最可能发生变化。
+    pub fn poll_soon(&self) {
+        self.scheduler.poll_soon();
+    }
+
+    /// 获取检查调度器的统计信息
+    pub fn scheduler_stats(&self) -> Result<SchedulerStats, PhotoKitListenerError> {
+        let watched: HashSet<Permission> = self
+            .get_active_listeners()?
+            .into_iter()
+            .map(|info| info.permission)
+            .collect();
+
+        self.scheduler.stats(watched.len())
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
 
     #[test]
     fn test_listener_info_creation() {
-        let info = ListenerInfo::new(PhotoKitAccessLevel::Read);
+        let info = ListenerInfo::new_with_target(PhotoKitAccessLevel::Read, EmitTarget::Broadcast);
 
         assert!(!info.id.is_empty());
-        assert_eq!(info.access_level, PhotoKitAccessLevel::Read);
+        assert_eq!(
+            info.permission,
+            Permission::PhotoKit(PhotoKitAccessLevel::Read)
+        );
+        assert_eq!(info.access_level(), Some(PhotoKitAccessLevel::Read));
         assert!(info.active);
         assert!(info.created_at > 0);
+        assert!(info.owner.is_none());
+        assert_eq!(info.with_owner("main").owner.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_listener_info_legacy_constructor() {
+        let info = ListenerInfo::new(PhotoKitAccessLevel::ReadWrite);
+
+        assert_eq!(info.access_level, Some(PhotoKitAccessLevel::ReadWrite));
+        assert_eq!(
+            info.permission,
+            Permission::PhotoKit(PhotoKitAccessLevel::ReadWrite)
+        );
+        assert_eq!(info.target, EmitTarget::Labels(vec!["main".to_string()]));
     }
 
-    // 注意：由于 Tauri 2.x 中测试 API 的变化，以下测试被暂时注释
-    // 在实际项目中，应该使用集成测试或模拟 AppHandle
+    #[test]
+    fn test_listener_info_keeps_access_level_field() {
+        let info = ListenerInfo::new_with_target(PhotoKitAccessLevel::Read, EmitTarget::Broadcast);
+        let value = serde_json::to_value(&info).unwrap();
+        assert_eq!(value["access_level"], "read");
+        assert_eq!(
+            value["permission"],
+            serde_json::json!({ "photoKit": "read" })
+        );
+
+        // 非 PhotoKit 权限省略旧字段
+        let info = ListenerInfo::new_with_target(Permission::Camera, EmitTarget::Broadcast);
+        let value = serde_json::to_value(&info).unwrap();
+        assert!(value.get("access_level").is_none());
+        assert_eq!(info.access_level(), None);
+    }
+
+    fn mock_app() -> tauri::App<MockRuntime> {
+        mock_builder()
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app")
+    }
 
-    /*
     #[test]
     fn test_listener_manager_creation() {
         let app = mock_app();
-        let manager = PhotoKitPermissionListener::new(app);
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
 
         // 验证管理器创建成功
         assert!(manager.get_active_listeners().unwrap().is_empty());
@@ -270,7 +1511,7 @@ mod tests {
     #[test]
     fn test_listener_registration_and_unregistration() {
         let app = mock_app();
-        let manager = PhotoKitPermissionListener::new(app);
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
 
         // 注册监听器
         let listener_id = manager
@@ -281,7 +1522,10 @@ mod tests {
         // 验证监听器已注册
         let listeners = manager.get_active_listeners().unwrap();
         assert_eq!(listeners.len(), 1);
-        assert_eq!(listeners[0].access_level, PhotoKitAccessLevel::ReadWrite);
+        assert_eq!(
+            listeners[0].access_level(),
+            Some(PhotoKitAccessLevel::ReadWrite)
+        );
 
         // 注销监听器
         assert!(manager.unregister_listener(&listener_id).is_ok());
@@ -289,12 +1533,18 @@ mod tests {
         // 验证监听器已注销
         let listeners = manager.get_active_listeners().unwrap();
         assert!(listeners.is_empty());
+
+        // 重复注销应该失败
+        assert!(matches!(
+            manager.unregister_listener(&listener_id),
+            Err(PhotoKitListenerError::ListenerNotFound(_))
+        ));
     }
 
     #[test]
     fn test_clear_all_listeners() {
         let app = mock_app();
-        let manager = PhotoKitPermissionListener::new(app);
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
 
         // 注册多个监听器
         let _id1 = manager
@@ -313,5 +1563,468 @@ mod tests {
         // 验证所有监听器已清除
         assert!(manager.get_active_listeners().unwrap().is_empty());
     }
-    */
+
+    #[test]
+    fn test_pause_and_resume_listener() {
+        let app = mock_app();
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = received.clone();
+        let guard = manager
+            .on_change(Permission::Camera, move |event| {
+                statuses.lock().unwrap().push(event.status);
+            })
+            .unwrap();
+
+        manager.pause_listener(guard.id()).unwrap();
+        assert!(manager.get_active_listeners().unwrap().is_empty());
+        let listeners = manager.get_all_listeners().unwrap();
+        assert_eq!(listeners.len(), 1);
+        assert!(!listeners[0].active);
+
+        // 暂停期间不接收事件
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Denied)
+            .unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        manager.resume_listener(guard.id()).unwrap();
+        assert!(manager.get_all_listeners().unwrap()[0].active);
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Authorized)
+            .unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PermissionStatus::Authorized]
+        );
+
+        assert!(matches!(
+            manager.pause_listener("missing"),
+            Err(PhotoKitListenerError::ListenerNotFound(_))
+        ));
+    }
+
+    /// 创建指定标签的窗口，返回该窗口收到的 `permission-changed` 事件计数
+    fn window_event_counter(app: &tauri::App<MockRuntime>, label: &str) -> Arc<Mutex<usize>> {
+        use tauri::Listener;
+
+        let window = tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::default())
+            .build()
+            .expect("failed to build mock window");
+
+        let count = Arc::new(Mutex::new(0));
+        let received = count.clone();
+        window.listen("permission-changed", move |_| {
+            *received.lock().unwrap() += 1;
+        });
+        count
+    }
+
+    #[test]
+    fn test_events_are_sent_to_listener_targets() {
+        let app = mock_app();
+        let main = window_event_counter(&app, "main");
+        let settings = window_event_counter(&app, "settings");
+        let other = window_event_counter(&app, "other");
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+
+        manager
+            .register_permission_listener_with_target(
+                Permission::Accessibility,
+                EmitTarget::Webview("settings".to_string()),
+            )
+            .unwrap();
+        // 默认目标来自配置的窗口标签
+        manager
+            .register_permission_listener(Permission::Accessibility)
+            .unwrap();
+        assert_eq!(
+            manager.default_target(),
+            &EmitTarget::Labels(vec!["main".to_string()])
+        );
+
+        manager
+            .handle_status_change(Permission::Accessibility, PermissionStatus::Authorized)
+            .unwrap();
+        assert_eq!(*main.lock().unwrap(), 1);
+        assert_eq!(*settings.lock().unwrap(), 1);
+        assert_eq!(*other.lock().unwrap(), 0);
+
+        manager
+            .register_permission_listener_with_target(
+                Permission::Accessibility,
+                EmitTarget::Broadcast,
+            )
+            .unwrap();
+        manager
+            .handle_status_change(Permission::Accessibility, PermissionStatus::Denied)
+            .unwrap();
+        assert_eq!(*main.lock().unwrap(), 2);
+        assert_eq!(*settings.lock().unwrap(), 2);
+        assert_eq!(*other.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_listener_target_resolution() {
+        let target: ListenerTarget = serde_json::from_value(serde_json::json!("caller")).unwrap();
+        assert_eq!(
+            target.resolve("settings"),
+            EmitTarget::Webview("settings".to_string())
+        );
+
+        let target: ListenerTarget =
+            serde_json::from_value(serde_json::json!({ "labels": ["a", "b"] })).unwrap();
+        assert_eq!(
+            target.resolve("settings"),
+            EmitTarget::Labels(vec!["a".to_string(), "b".to_string()])
+        );
+
+        assert_eq!(
+            serde_json::to_value(EmitTarget::Webview("main".to_string())).unwrap(),
+            serde_json::json!({ "webview": "main" })
+        );
+        assert_eq!(EmitTarget::from_labels(Vec::new()), EmitTarget::Broadcast);
+    }
+
+    /// 创建把收到的消息记录下来的通道
+    fn recording_channel() -> (Channel<PermissionChangedEvent>, Arc<Mutex<Vec<String>>>) {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let received = messages.clone();
+        let channel = Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                received.lock().unwrap().push(json);
+            }
+            Ok(())
+        });
+        (channel, messages)
+    }
+
+    #[test]
+    fn test_watch_permission_streams_to_channel() {
+        let app = mock_app();
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+        let (channel, messages) = recording_channel();
+        let channel_id = channel.id();
+
+        let listener_id = manager
+            .watch_permission(
+                Permission::Camera,
+                channel,
+                "main".to_string(),
+                TransitionFilter::default(),
+            )
+            .unwrap();
+        let listeners = manager.get_active_listeners().unwrap();
+        assert_eq!(listeners[0].target, EmitTarget::Channel(channel_id));
+
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Denied)
+            .unwrap();
+        manager
+            .handle_status_change(Permission::Microphone, PermissionStatus::Denied)
+            .unwrap();
+
+        let received = messages.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&received[0]).unwrap();
+        assert_eq!(payload["permission"], "camera");
+        assert_eq!(payload["status"], "denied");
+
+        manager.unregister_listener(&listener_id).unwrap();
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Authorized)
+            .unwrap();
+        assert_eq!(messages.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_listeners_are_removed_when_channel_or_webview_goes_away() {
+        let app = mock_app();
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+
+        // 发送失败的通道视为已关闭
+        let closed = Channel::new(|_| Err(tauri::Error::WebviewNotFound));
+        manager
+            .watch_permission(
+                Permission::Camera,
+                closed,
+                "main".to_string(),
+                TransitionFilter::default(),
+            )
+            .unwrap();
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Denied)
+            .unwrap();
+        assert!(manager.get_active_listeners().unwrap().is_empty());
+
+        let (settings_channel, _) = recording_channel();
+        let (main_channel, _) = recording_channel();
+        manager
+            .watch_permission(
+                Permission::Camera,
+                settings_channel,
+                "settings".to_string(),
+                TransitionFilter::default(),
+            )
+            .unwrap();
+        manager
+            .watch_permission(
+                Permission::Camera,
+                main_channel,
+                "main".to_string(),
+                TransitionFilter::default(),
+            )
+            .unwrap();
+
+        // 事件监听器同样归属注册它的 webview
+        manager
+            .register_webview_listener(
+                Permission::Camera,
+                EmitTarget::Broadcast,
+                "settings",
+                TransitionFilter::default(),
+            )
+            .unwrap();
+        let rust_listener = manager
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+
+        manager.remove_webview_listeners("settings").unwrap();
+        let listeners = manager.get_active_listeners().unwrap();
+        assert_eq!(listeners.len(), 2);
+        assert!(listeners
+            .iter()
+            .any(|info| info.id == rust_listener && info.owner.is_none()));
+        assert!(listeners
+            .iter()
+            .any(|info| info.owner.as_deref() == Some("main")));
+        assert_eq!(manager.subscribers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_event_history_is_bounded_and_filtered() {
+        let app = mock_app();
+        let clock = Arc::new(crate::ManualClock::new(1_000));
+        let manager = PhotoKitPermissionListener::with_event_targets(app.handle().clone(), vec![])
+            .with_clock(clock.clone())
+            .with_history_capacity(2);
+        let read = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+        manager.register_permission_listener(read).unwrap();
+        manager
+            .register_permission_listener(Permission::ScreenRecording)
+            .unwrap();
+
+        manager
+            .handle_status_change(read, PermissionStatus::Limited)
+            .unwrap();
+        clock.advance(Duration::from_secs(1));
+        manager
+            .handle_status_change(Permission::ScreenRecording, PermissionStatus::Authorized)
+            .unwrap();
+        clock.advance(Duration::from_secs(1));
+        manager
+            .handle_status_change(read, PermissionStatus::Authorized)
+            .unwrap();
+
+        // 超出容量时丢弃最旧的事件
+        let history = manager.event_history(0, None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].permission, Permission::ScreenRecording);
+        assert_eq!(history[0].timestamp, 2_000);
+        assert_eq!(history[1].previous_status, Some(PermissionStatus::Limited));
+
+        let history = manager.event_history(3_000, None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, PermissionStatus::Authorized);
+
+        let history = manager
+            .event_history(0, Some(Permission::ScreenRecording))
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, PermissionStatus::Authorized);
+    }
+
+    #[test]
+    fn test_transition_filter() {
+        use PermissionStatus::*;
+
+        assert!(TransitionFilter::OnAnyChange.matches(Some(Denied), Authorized));
+        assert!(TransitionFilter::OnGrant.matches(Some(NotDetermined), Limited));
+        assert!(TransitionFilter::OnGrant.matches(None, Authorized));
+        assert!(!TransitionFilter::OnGrant.matches(Some(Limited), Authorized));
+        assert!(TransitionFilter::OnRevoke.matches(Some(Authorized), Denied));
+        assert!(!TransitionFilter::OnRevoke.matches(None, Denied));
+        assert!(TransitionFilter::Statuses(vec![Denied, Restricted]).matches(None, Restricted));
+        assert!(!TransitionFilter::Statuses(vec![Denied]).matches(Some(Denied), Authorized));
+
+        let filter: TransitionFilter =
+            serde_json::from_value(serde_json::json!({ "statuses": ["denied"] })).unwrap();
+        assert_eq!(filter, TransitionFilter::Statuses(vec![Denied]));
+        let filter: TransitionFilter =
+            serde_json::from_value(serde_json::json!("onGrant")).unwrap();
+        assert_eq!(filter, TransitionFilter::OnGrant);
+    }
+
+    #[test]
+    fn test_filtered_listeners_only_receive_matching_changes() {
+        let app = mock_app();
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+        let mut received = Vec::new();
+        for filter in [
+            TransitionFilter::OnGrant,
+            TransitionFilter::OnRevoke,
+            TransitionFilter::Statuses(vec![PermissionStatus::Denied]),
+        ] {
+            let (channel, messages) = recording_channel();
+            manager
+                .watch_permission(Permission::Camera, channel, "main".to_string(), filter)
+                .unwrap();
+            received.push(messages);
+        }
+
+        manager
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::NotDetermined,
+                ChangeCause::ExternalChange,
+            )
+            .unwrap();
+        manager
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::Authorized,
+                ChangeCause::UserRequest,
+            )
+            .unwrap();
+        manager
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::Denied,
+                ChangeCause::ExternalChange,
+            )
+            .unwrap();
+
+        let counts: Vec<usize> = received
+            .iter()
+            .map(|messages| messages.lock().unwrap().len())
+            .collect();
+        assert_eq!(counts, vec![1, 1, 1]);
+        let granted: serde_json::Value =
+            serde_json::from_str(&received[0].lock().unwrap()[0]).unwrap();
+        assert_eq!(granted["status"], "authorized");
+
+        // 历史记录不受过滤条件影响
+        assert_eq!(manager.event_history(0, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_reconciled_changes_respect_filters() {
+        use tauri::Listener;
+
+        let app = mock_app();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = received.clone();
+        app.listen_any("permission-changed", move |event| {
+            events.lock().unwrap().push(event.payload().to_string());
+        });
+
+        let manager = PhotoKitPermissionListener::with_event_targets(app.handle().clone(), vec![]);
+        manager
+            .register_webview_listener(
+                Permission::Camera,
+                EmitTarget::Broadcast,
+                "main",
+                TransitionFilter::OnGrant,
+            )
+            .unwrap();
+
+        // 所有监听器都拒绝的撤销事件不会发送到默认目标
+        manager
+            .reconcile_status(
+                Permission::Camera,
+                PermissionStatus::Authorized,
+                PermissionStatus::Denied,
+            )
+            .unwrap();
+        assert!(received.lock().unwrap().is_empty());
+        assert_eq!(manager.event_history(0, None).unwrap().len(), 1);
+
+        // 没有监听器关注的权限发送到默认目标
+        manager
+            .reconcile_status(
+                Permission::Microphone,
+                PermissionStatus::Authorized,
+                PermissionStatus::Denied,
+            )
+            .unwrap();
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&received[0]).unwrap();
+        assert_eq!(payload["permission"], "microphone");
+        assert_eq!(payload["cause"], "startupReconciliation");
+    }
+
+    #[test]
+    fn test_initial_status_reuses_baseline_of_other_listeners() {
+        let app = mock_app();
+        let backend = Arc::new(crate::FakePermissionBackend::new());
+        backend.set_status(Permission::FullDiskAccess, PermissionStatus::Authorized);
+        app.manage(PhotoKitPermissionManager::with_backend(
+            backend.clone(),
+            None,
+        ));
+        let manager =
+            PhotoKitPermissionListener::new(app.handle().clone()).with_polling_interval(None);
+
+        let _first = manager
+            .on_change(Permission::FullDiskAccess, |_| {})
+            .unwrap();
+
+        // 已有基准状态时不再等待慢的检查
+        backend.set_check_delay(Permission::FullDiskAccess, Duration::from_secs(5));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = received.clone();
+        let started = std::time::Instant::now();
+        let _second = manager
+            .on_change(Permission::FullDiskAccess, move |event| {
+                statuses.lock().unwrap().push((event.cause, event.status));
+            })
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![(ChangeCause::Initial, PermissionStatus::Authorized)]
+        );
+    }
+
+    #[test]
+    fn test_panicking_callback_is_unsubscribed() {
+        let app = mock_app();
+        let manager = PhotoKitPermissionListener::new(app.handle().clone());
+        let _panicking = manager
+            .on_change(Permission::Camera, |_| panic!("callback failed"))
+            .unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = received.clone();
+        let _guard = manager
+            .on_change(Permission::Camera, move |event| {
+                statuses.lock().unwrap().push(event.status);
+            })
+            .unwrap();
+
+        // panic 不会传播到分发线程，其他订阅者照常收到事件
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Denied)
+            .unwrap();
+        assert_eq!(manager.get_active_listeners().unwrap().len(), 1);
+
+        manager
+            .handle_status_change(Permission::Camera, PermissionStatus::Authorized)
+            .unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PermissionStatus::Denied, PermissionStatus::Authorized]
+        );
+    }
 }
@@ -3,12 +3,16 @@
 //! 此模块提供 PhotoKit 权限管理的核心逻辑，包括权限状态检查、权限请求协调、
 //! 跨平台兼容性处理、错误处理和线程安全的状态管理。
 
-use crate::{PhotoKitAccessLevel, PhotoKitAuthorizationStatus};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use crate::{
+    default_backend, Permission, PermissionError, PermissionKind, PermissionSnapshot,
+    PermissionStatus, PhotoKitAccessLevel, PhotoKitAuthorizationStatus, SharedPermissionBackend,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "macos")]
-use crate::photokit_bridge::{PhotoKitBridge, PhotoKitBridgeError};
+use crate::photokit_bridge::PhotoKitBridgeError;
 
 /// PhotoKit 权限管理器错误类型
 #[derive(Debug, thiserror::Error)]
@@ -21,6 +25,8 @@ pub enum PhotoKitManagerError {
     RequestFailed(String),
     #[error("状态缓存错误: {0}")]
     CacheError(String),
+    #[error(transparent)]
+    Permission(#[from] PermissionError),
     #[cfg(target_os = "macos")]
     #[error("桥接层错误: {0}")]
     BridgeError(#[from] PhotoKitBridgeError),
@@ -33,34 +39,141 @@ struct CacheEntry {
     timestamp: std::time::SystemTime,
 }
 
+/// 正在进行中的权限请求
+///
+/// 同一权限的并发请求共享同一个实例，发起者完成请求后把结果写入 `result` 并唤醒其他等待者。
+#[derive(Default)]
+struct InFlightRequest {
+    result: Mutex<Option<Result<PermissionStatus, PermissionError>>>,
+    completed: Condvar,
+}
+
+impl InFlightRequest {
+    /// 写入请求结果并唤醒所有等待者，只有第一次写入生效
+    fn complete(&self, result: Result<PermissionStatus, PermissionError>) {
+        let mut slot = self
+            .result
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        slot.get_or_insert(result);
+        self.completed.notify_all();
+    }
+
+    /// 等待请求完成并返回结果
+    fn wait(&self) -> Result<PermissionStatus, PermissionError> {
+        let slot = self
+            .result
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let slot = self
+            .completed
+            .wait_while(slot, |result| result.is_none())
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        slot.clone()
+            .unwrap_or_else(|| Err(PermissionError::request_failed("权限请求已中断")))
+    }
+}
+
+/// 请求发起者持有的守卫
+///
+/// 无论请求正常结束还是发生 panic，都会把请求从进行中列表移除并唤醒等待者。
+struct InFlightGuard<'a> {
+    in_flight: &'a Mutex<HashMap<Permission, Arc<InFlightRequest>>>,
+    permission: Permission,
+    request: Arc<InFlightRequest>,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.permission);
+        self.request
+            .complete(Err(PermissionError::request_failed("权限请求已中断")));
+    }
+}
+
 /// PhotoKit 权限管理器
 ///
 /// 提供线程安全的 PhotoKit 权限管理功能，包括状态检查、权限请求和状态缓存。
+/// 实际的系统调用由注入的 `PermissionBackend` 完成。
+/// 克隆得到的实例与原实例共享后端和状态缓存。
+#[derive(Clone)]
 pub struct PhotoKitPermissionManager {
-    #[cfg(target_os = "macos")]
-    bridge: PhotoKitBridge,
+    /// 权限后端
+    backend: SharedPermissionBackend,
 
     /// 权限状态缓存，按权限级别存储
     cache: Arc<Mutex<HashMap<PhotoKitAccessLevel, CacheEntry>>>,
 
     /// 缓存过期时间（秒）
     cache_ttl: u64,
+
+    /// 启用的权限类型，为 None 时启用全部权限
+    enabled_permissions: Option<Vec<PermissionKind>>,
+
+    /// 正在进行中的权限请求，用于合并同一权限的并发请求
+    in_flight: Arc<Mutex<HashMap<Permission, Arc<InFlightRequest>>>>,
+
+    /// 带超时的检查中仍在后台线程上执行的检查
+    probing: Arc<Mutex<HashSet<Permission>>>,
+}
+
+/// 检查线程结束时（包括 panic）把权限移出进行中的检查
+struct ProbeGuard {
+    probing: Arc<Mutex<HashSet<Permission>>>,
+    permission: Permission,
+}
+
+impl Drop for ProbeGuard {
+    fn drop(&mut self) {
+        if let Ok(mut probing) = self.probing.lock() {
+            probing.remove(&self.permission);
+        }
+    }
 }
 
 impl PhotoKitPermissionManager {
-    /// 创建新的 PhotoKit 权限管理器实例
+    /// 创建使用当前平台默认后端的 PhotoKit 权限管理器实例
     ///
     /// # Arguments
     /// * `cache_ttl` - 缓存过期时间（秒），默认为 30 秒
     pub fn new(cache_ttl: Option<u64>) -> Self {
+        Self::with_backend(default_backend(), cache_ttl)
+    }
+
+    /// 创建使用指定后端的 PhotoKit 权限管理器实例
+    ///
+    /// # Arguments
+    /// * `backend` - 执行实际权限操作的后端
+    /// * `cache_ttl` - 缓存过期时间（秒），默认为 30 秒
+    pub fn with_backend(backend: SharedPermissionBackend, cache_ttl: Option<u64>) -> Self {
         Self {
-            #[cfg(target_os = "macos")]
-            bridge: PhotoKitBridge::new(),
+            backend,
             cache: Arc::new(Mutex::new(HashMap::new())),
             cache_ttl: cache_ttl.unwrap_or(30),
+            enabled_permissions: None,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            probing: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
+    /// 限制管理器可以检查和请求的权限类型
+    ///
+    /// 对未启用的权限进行检查或请求时返回 `PermissionDisabled` 错误。
+    ///
+    /// # Arguments
+    /// * `enabled_permissions` - 启用的权限类型，为 None 时启用全部权限
+    pub fn with_enabled_permissions(
+        mut self,
+        enabled_permissions: Option<Vec<PermissionKind>>,
+    ) -> Self {
+        self.enabled_permissions = enabled_permissions;
+        self
+    }
+
     /// 检查指定权限级别的当前授权状态
     ///
     /// 此方法首先检查缓存，如果缓存有效则直接返回，否则调用底层 API 获取最新状态。
@@ -77,6 +190,8 @@ impl PhotoKitPermissionManager {
         &self,
         access_level: PhotoKitAccessLevel,
     ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        self.ensure_available(Permission::PhotoKit(access_level))?;
+
         // 首先检查缓存
         if let Some(cached_status) = self.get_cached_status(access_level)? {
             return Ok(cached_status);
@@ -91,9 +206,28 @@ impl PhotoKitPermissionManager {
         Ok(status)
     }
 
+    /// 绕过缓存重新检查指定权限级别的授权状态，并用结果刷新缓存
+    ///
+    /// 供权限变化检测使用，保证每次检查都能看到系统中的最新状态。
+    ///
+    /// # Arguments
+    /// * `access_level` - 要检查的权限级别
+    pub fn refresh_authorization_status(
+        &self,
+        access_level: PhotoKitAccessLevel,
+    ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
+        self.ensure_available(Permission::PhotoKit(access_level))?;
+
+        let status = self.check_status_from_system(access_level)?;
+        self.update_cache(access_level, status)?;
+
+        Ok(status)
+    }
+
     /// 请求指定权限级别的授权
     ///
     /// 此方法会触发系统权限请求对话框，并在用户响应后更新缓存。
+    /// 同一权限级别的并发请求会被合并，只弹出一次对话框，所有调用者得到相同的结果。
     ///
     /// # Arguments
     /// * `access_level` - 要请求的权限级别
@@ -107,14 +241,266 @@ impl PhotoKitPermissionManager {
         &self,
         access_level: PhotoKitAccessLevel,
     ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
-        let status = self.request_authorization_from_system(access_level)?;
+        let permission = Permission::PhotoKit(access_level);
+        self.ensure_available(permission)?;
 
-        // 更新缓存
-        self.update_cache(access_level, status)?;
+        let status = self.coalesce_request(permission, || {
+            let status = self.request_authorization_from_system(access_level)?;
+
+            // 更新缓存
+            self.update_cache(access_level, status)?;
+
+            Ok(status.into())
+        })?;
+
+        Ok(status.into())
+    }
+
+    /// 检查任意权限的当前授权状态
+    ///
+    /// PhotoKit 权限会经过状态缓存。
+    ///
+    /// # Arguments
+    /// * `permission` - 要检查的权限
+    ///
+    /// # Errors
+    /// 如果权限检查失败，返回相应的错误
+    pub fn check_permission(
+        &self,
+        permission: Permission,
+    ) -> Result<PermissionStatus, PhotoKitManagerError> {
+        self.ensure_available(permission)?;
+
+        let status = match permission {
+            Permission::Accessibility => self.backend.check_accessibility(),
+            Permission::FullDiskAccess => self.backend.check_full_disk_access(),
+            Permission::ScreenRecording => self.backend.check_screen_recording(),
+            Permission::Microphone => self.backend.check_microphone(),
+            Permission::Camera => self.backend.check_camera(),
+            Permission::InputMonitoring => self.backend.check_input_monitoring(),
+            Permission::PhotoKit(access_level) => {
+                self.check_authorization_status(access_level)?.into()
+            }
+        };
 
         Ok(status)
     }
 
+    /// 绕过缓存重新检查指定权限的授权状态
+    ///
+    /// 供权限变化检测使用。PhotoKit 权限的结果会刷新缓存。
+    ///
+    /// # Arguments
+    /// * `permission` - 要检查的权限
+    pub fn refresh_permission(
+        &self,
+        permission: Permission,
+    ) -> Result<PermissionStatus, PhotoKitManagerError> {
+        match permission {
+            Permission::PhotoKit(access_level) => self
+                .refresh_authorization_status(access_level)
+                .map(Into::into),
+            _ => self.check_permission(permission),
+        }
+    }
+
+    /// 绕过缓存重新检查指定权限，最多等待 `probe_timeout`
+    ///
+    /// 与 `check_all_permissions` 共用进行中的检查：上一次的检查仍未返回时直接返回 None，
+    /// 不再启动新线程。
+    ///
+    /// # Arguments
+    /// * `permission` - 要检查的权限
+    /// * `probe_timeout` - 检查的超时时间
+    ///
+    /// # Returns
+    /// 检查超时时返回 None
+    pub(crate) fn refresh_permission_within(
+        &self,
+        permission: Permission,
+        probe_timeout: Duration,
+    ) -> Option<Result<PermissionStatus, PhotoKitManagerError>> {
+        let (sender, receiver) = mpsc::channel();
+        if !self.spawn_probe(permission, Self::refresh_permission, sender) {
+            return None;
+        }
+
+        receiver
+            .recv_timeout(probe_timeout)
+            .ok()
+            .map(|(_, result)| result)
+    }
+
+    /// 在后台线程上检查权限，并把结果发送到 `sender`
+    ///
+    /// # Returns
+    /// 上一次对此权限的检查仍未返回时不启动新线程，返回 false
+    fn spawn_probe<T: Send + 'static>(
+        &self,
+        permission: Permission,
+        probe: fn(&Self, Permission) -> T,
+        sender: mpsc::Sender<(Permission, T)>,
+    ) -> bool {
+        let idle = self
+            .probing
+            .lock()
+            .is_ok_and(|mut probing| probing.insert(permission));
+        if !idle {
+            return false;
+        }
+
+        let manager = self.clone();
+        let guard = ProbeGuard {
+            probing: self.probing.clone(),
+            permission,
+        };
+        std::thread::spawn(move || {
+            let result = probe(&manager, permission);
+            drop(guard);
+            let _ = sender.send((permission, result));
+        });
+        true
+    }
+
+    /// 并发检查所有已启用的权限，返回权限快照
+    ///
+    /// 每项权限在独立的线程上检查，超过 `probe_timeout` 仍未返回的权限记录在 `timed_out` 中，
+    /// 检查出错的权限记录在 `failed` 中。未启用的权限不会出现在快照里。
+    ///
+    /// 超时的检查线程无法取消，上一次的检查仍未返回的权限直接记为超时，不再启动新线程，
+    /// 因此卡住的系统调用最多占用每项权限一个线程。
+    ///
+    /// # Arguments
+    /// * `probe_timeout` - 每项权限检查的超时时间
+    pub fn check_all_permissions(&self, probe_timeout: Duration) -> PermissionSnapshot {
+        let permissions: Vec<Permission> = Permission::ALL
+            .into_iter()
+            .filter(|&permission| self.is_enabled(permission))
+            .collect();
+
+        let (sender, receiver) = mpsc::channel();
+        let mut started = 0;
+        for &permission in &permissions {
+            if self.spawn_probe(permission, Self::check_permission, sender.clone()) {
+                started += 1;
+            }
+        }
+        drop(sender);
+
+        // 所有检查同时开始，因此共用同一个截止时间
+        let deadline = Instant::now() + probe_timeout;
+        let mut results = HashMap::new();
+        while results.len() < started {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok((permission, result)) => {
+                    results.insert(permission, result);
+                }
+                Err(_) => break,
+            }
+        }
+
+        let mut statuses = HashMap::new();
+        let mut timed_out = Vec::new();
+        let mut failed = Vec::new();
+        for permission in permissions {
+            match results.remove(&permission) {
+                Some(Ok(status)) => {
+                    statuses.insert(permission, status);
+                }
+                Some(Err(_)) => failed.push(permission),
+                None => timed_out.push(permission),
+            }
+        }
+
+        PermissionSnapshot {
+            statuses,
+            timed_out,
+            failed,
+            captured_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        }
+    }
+
+    /// 请求任意权限，并返回请求后的授权状态
+    ///
+    /// 对于会弹出系统对话框的权限，此方法会阻塞到用户作出选择或达到 `REQUEST_TIMEOUT`，
+    /// 因此不要在异步运行时的工作线程上直接调用。
+    ///
+    /// # Arguments
+    /// * `permission` - 要请求的权限
+    ///
+    /// # Errors
+    /// 如果权限请求失败，返回相应的错误
+    pub fn request_permission(
+        &self,
+        permission: Permission,
+    ) -> Result<PermissionStatus, PhotoKitManagerError> {
+        if let Permission::PhotoKit(access_level) = permission {
+            return Ok(self.request_authorization(access_level)?.into());
+        }
+
+        self.ensure_available(permission)?;
+
+        self.coalesce_request(permission, || {
+            match permission {
+                Permission::Accessibility => self.backend.request_accessibility(),
+                Permission::FullDiskAccess => self.backend.request_full_disk_access()?,
+                Permission::ScreenRecording => self.backend.request_screen_recording(),
+                Permission::Microphone => return Ok(self.backend.request_microphone()?),
+                Permission::Camera => return Ok(self.backend.request_camera()?),
+                Permission::InputMonitoring => self.backend.request_input_monitoring()?,
+                Permission::PhotoKit(_) => {}
+            }
+
+            self.check_permission(permission)
+        })
+    }
+
+    /// 合并同一权限的并发请求
+    ///
+    /// 第一个调用者执行 `request`，在它完成之前到达的其他调用者等待并得到相同的结果。
+    fn coalesce_request(
+        &self,
+        permission: Permission,
+        request: impl FnOnce() -> Result<PermissionStatus, PhotoKitManagerError>,
+    ) -> Result<PermissionStatus, PhotoKitManagerError> {
+        let (in_flight, is_leader) = {
+            let mut in_flight =
+                self.in_flight
+                    .lock()
+                    .map_err(|_| PermissionError::LockPoisoned {
+                        resource: "inFlightRequests".to_string(),
+                    })?;
+
+            match in_flight.get(&permission) {
+                Some(in_flight) => (in_flight.clone(), false),
+                None => {
+                    let request = Arc::new(InFlightRequest::default());
+                    in_flight.insert(permission, request.clone());
+                    (request, true)
+                }
+            }
+        };
+
+        if !is_leader {
+            return Ok(in_flight.wait()?);
+        }
+
+        let guard = InFlightGuard {
+            in_flight: &self.in_flight,
+            permission,
+            request: in_flight,
+        };
+        let result = request().map_err(PermissionError::from);
+        guard.request.complete(result.clone());
+        drop(guard);
+
+        Ok(result?)
+    }
+
     /// 清除指定权限级别的缓存
     ///
     /// # Arguments
@@ -145,15 +531,7 @@ impl PhotoKitPermissionManager {
     /// # Returns
     /// 如果 PhotoKit 框架可用返回 true，否则返回 false
     pub fn is_framework_available(&self) -> bool {
-        #[cfg(target_os = "macos")]
-        {
-            self.bridge.is_framework_available()
-        }
-
-        #[cfg(not(target_os = "macos"))]
-        {
-            false
-        }
+        self.backend.is_photokit_available()
     }
 
     /// 获取照片库中的总照片数量
@@ -167,18 +545,24 @@ impl PhotoKitPermissionManager {
     /// # Errors
     /// 如果没有权限或查询失败，返回相应的错误
     pub fn get_photos_count(&self) -> Result<u64, PhotoKitManagerError> {
-        #[cfg(target_os = "macos")]
-        {
-            self.bridge
-                .get_photos_count()
-                .map_err(PhotoKitManagerError::from)
-        }
+        self.ensure_available(Permission::PhotoKit(PhotoKitAccessLevel::Read))?;
+        self.backend.get_photos_count()
+    }
 
-        #[cfg(not(target_os = "macos"))]
-        {
-            // 在非 macOS 平台，返回 0 以保持兼容性
-            Ok(0)
+    /// 检查指定权限是否已在配置中启用
+    pub fn is_enabled(&self, permission: Permission) -> bool {
+        self.enabled_permissions
+            .as_ref()
+            .map_or(true, |kinds| kinds.contains(&permission.kind()))
+    }
+
+    /// 确认指定权限已在配置中启用，并且当前后端可以处理
+    fn ensure_available(&self, permission: Permission) -> Result<(), PermissionError> {
+        if !self.is_enabled(permission) {
+            return Err(PermissionError::PermissionDisabled { permission });
         }
+
+        self.backend.ensure_supported(permission)
     }
 
     /// 从缓存获取权限状态
@@ -230,18 +614,7 @@ impl PhotoKitPermissionManager {
         &self,
         access_level: PhotoKitAccessLevel,
     ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
-        #[cfg(target_os = "macos")]
-        {
-            self.bridge
-                .check_authorization_status(access_level)
-                .map_err(PhotoKitManagerError::from)
-        }
-
-        #[cfg(not(target_os = "macos"))]
-        {
-            // 在非 macOS 平台，返回已授权状态以保持兼容性
-            Ok(PhotoKitAuthorizationStatus::Authorized)
-        }
+        self.backend.check_photokit(access_level)
     }
 
     /// 从系统请求权限授权
@@ -249,18 +622,7 @@ impl PhotoKitPermissionManager {
         &self,
         access_level: PhotoKitAccessLevel,
     ) -> Result<PhotoKitAuthorizationStatus, PhotoKitManagerError> {
-        #[cfg(target_os = "macos")]
-        {
-            self.bridge
-                .request_authorization(access_level)
-                .map_err(PhotoKitManagerError::from)
-        }
-
-        #[cfg(not(target_os = "macos"))]
-        {
-            // 在非 macOS 平台，直接返回已授权状态
-            Ok(PhotoKitAuthorizationStatus::Authorized)
-        }
+        self.backend.request_photokit(access_level)
     }
 }
 
@@ -270,13 +632,10 @@ impl Default for PhotoKitPermissionManager {
     }
 }
 
-// 线程安全标记
-unsafe impl Send for PhotoKitPermissionManager {}
-unsafe impl Sync for PhotoKitPermissionManager {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::FakePermissionBackend;
 
     #[test]
     fn test_manager_creation() {
@@ -299,6 +658,273 @@ mod tests {
         assert!(manager.clear_cache(Some(PhotoKitAccessLevel::Read)).is_ok());
     }
 
+    #[test]
+    fn test_check_and_request_with_fake_backend() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+
+        assert_eq!(
+            manager
+                .check_authorization_status(PhotoKitAccessLevel::Read)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::NotDetermined
+        );
+
+        assert_eq!(
+            manager
+                .request_authorization(PhotoKitAccessLevel::Read)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Authorized
+        );
+        assert_eq!(backend.request_count(), 1);
+
+        // 请求结果会写入缓存
+        backend.set_status(
+            Permission::PhotoKit(PhotoKitAccessLevel::Read),
+            PermissionStatus::Denied,
+        );
+        assert_eq!(
+            manager
+                .check_authorization_status(PhotoKitAccessLevel::Read)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Authorized
+        );
+
+        // 清除缓存后重新读取后端状态
+        manager
+            .clear_cache(Some(PhotoKitAccessLevel::Read))
+            .unwrap();
+        assert_eq!(
+            manager
+                .check_authorization_status(PhotoKitAccessLevel::Read)
+                .unwrap(),
+            PhotoKitAuthorizationStatus::Denied
+        );
+    }
+
+    #[test]
+    fn test_check_all_permissions_times_out_slow_probes() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        backend.set_check_delay(Permission::FullDiskAccess, Duration::from_millis(300));
+        let manager = PhotoKitPermissionManager::with_backend(backend, None)
+            .with_enabled_permissions(Some(vec![
+                PermissionKind::Camera,
+                PermissionKind::FullDiskAccess,
+                PermissionKind::PhotoKit,
+            ]));
+
+        let started = Instant::now();
+        let snapshot = manager.check_all_permissions(Duration::from_millis(50));
+
+        // 慢的检查不会拖住整个快照
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert_eq!(snapshot.timed_out, vec![Permission::FullDiskAccess]);
+        assert_eq!(
+            snapshot.get(Permission::Camera),
+            Some(PermissionStatus::Authorized)
+        );
+        assert_eq!(snapshot.statuses.len(), 4);
+        assert_eq!(snapshot.get(Permission::Microphone), None);
+    }
+
+    #[test]
+    fn test_check_all_permissions_skips_probes_still_running() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_check_delay(Permission::FullDiskAccess, Duration::from_millis(300));
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None)
+            .with_enabled_permissions(Some(vec![
+                PermissionKind::Camera,
+                PermissionKind::FullDiskAccess,
+            ]));
+
+        let snapshot = manager.check_all_permissions(Duration::from_millis(20));
+        assert_eq!(snapshot.timed_out, vec![Permission::FullDiskAccess]);
+
+        // 上一次的检查仍未返回，不再启动新线程等待它
+        let started = Instant::now();
+        let snapshot = manager.check_all_permissions(Duration::from_secs(2));
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert_eq!(snapshot.timed_out, vec![Permission::FullDiskAccess]);
+        assert!(snapshot.get(Permission::Camera).is_some());
+
+        // 卡住的检查返回后重新检查
+        backend.set_check_delay(Permission::FullDiskAccess, Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(400));
+        let snapshot = manager.check_all_permissions(Duration::from_secs(2));
+        assert!(snapshot.timed_out.is_empty());
+        assert!(snapshot.get(Permission::FullDiskAccess).is_some());
+    }
+
+    #[test]
+    fn test_refresh_permission_within_times_out() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        backend.set_check_delay(Permission::FullDiskAccess, Duration::from_millis(300));
+        let manager = PhotoKitPermissionManager::with_backend(backend, None);
+
+        assert!(matches!(
+            manager.refresh_permission_within(Permission::Camera, Duration::from_secs(2)),
+            Some(Ok(PermissionStatus::Authorized))
+        ));
+
+        let started = Instant::now();
+        assert!(manager
+            .refresh_permission_within(Permission::FullDiskAccess, Duration::from_millis(50))
+            .is_none());
+        assert!(started.elapsed() < Duration::from_millis(250));
+
+        // 卡住的检查仍未返回时不再等待
+        let started = Instant::now();
+        assert!(manager
+            .refresh_permission_within(Permission::FullDiskAccess, Duration::from_secs(2))
+            .is_none());
+        assert!(started.elapsed() < Duration::from_millis(200));
+    }
+
+    /// 在 `count` 个线程上同时调用 `request`，返回所有结果
+    fn concurrent_requests<T: Send + 'static>(
+        count: usize,
+        request: impl Fn() -> T + Send + Sync + 'static,
+    ) -> Vec<T> {
+        let request = Arc::new(request);
+        let barrier = Arc::new(std::sync::Barrier::new(count));
+        let handles: Vec<_> = (0..count)
+            .map(|_| {
+                let request = request.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    request()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_concurrent_requests_are_coalesced() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_request_delay(Duration::from_millis(200));
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+
+        let camera_manager = manager.clone();
+        let results = concurrent_requests(8, move || {
+            camera_manager
+                .request_permission(Permission::Camera)
+                .map_err(PermissionError::from)
+        });
+        assert_eq!(backend.request_count(), 1);
+        assert!(results
+            .iter()
+            .all(|result| result == &Ok(PermissionStatus::Authorized)));
+
+        backend.set_grant_on_request(false);
+        let photokit_manager = manager.clone();
+        let results = concurrent_requests(8, move || {
+            photokit_manager
+                .request_authorization(PhotoKitAccessLevel::ReadWrite)
+                .map_err(PermissionError::from)
+        });
+        assert_eq!(backend.request_count(), 2);
+        assert!(results
+            .iter()
+            .all(|result| result == &Ok(PhotoKitAuthorizationStatus::Denied)));
+
+        // 请求完成后，新的请求会再次调用后端
+        manager.request_permission(Permission::Camera).unwrap();
+        assert_eq!(backend.request_count(), 3);
+    }
+
+    #[test]
+    fn test_coalesced_requests_share_errors() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_request_delay(Duration::from_millis(200));
+        backend.set_usage_description(Permission::Microphone, false);
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+
+        let results = concurrent_requests(4, move || {
+            manager
+                .request_permission(Permission::Microphone)
+                .map_err(PermissionError::from)
+        });
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(PermissionError::MissingUsageDescription { .. }))));
+        assert_eq!(backend.request_count(), 0);
+    }
+
+    #[test]
+    fn test_different_permissions_are_not_coalesced() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        backend.set_request_delay(Duration::from_millis(100));
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+
+        let counter = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        concurrent_requests(2, move || {
+            let permission = if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Permission::Camera
+            } else {
+                Permission::Microphone
+            };
+            manager.request_permission(permission).unwrap()
+        });
+        assert_eq!(backend.request_count(), 2);
+    }
+
+    #[test]
+    fn test_generic_permission_dispatch() {
+        let backend = Arc::new(FakePermissionBackend::new());
+        let manager = PhotoKitPermissionManager::with_backend(backend.clone(), None);
+
+        backend.set_status(Permission::InputMonitoring, PermissionStatus::Authorized);
+        backend.set_status(Permission::Microphone, PermissionStatus::Restricted);
+        assert_eq!(
+            manager
+                .check_permission(Permission::InputMonitoring)
+                .unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(
+            manager.check_permission(Permission::Microphone).unwrap(),
+            PermissionStatus::Restricted
+        );
+        assert_eq!(
+            manager.check_permission(Permission::Camera).unwrap(),
+            PermissionStatus::NotDetermined
+        );
+        assert_eq!(
+            manager
+                .check_permission(Permission::PhotoKit(PhotoKitAccessLevel::AddOnly))
+                .unwrap(),
+            PermissionStatus::NotDetermined
+        );
+
+        assert_eq!(
+            manager.request_permission(Permission::Camera).unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(
+            manager
+                .request_permission(Permission::PhotoKit(PhotoKitAccessLevel::AddOnly))
+                .unwrap(),
+            PermissionStatus::Authorized
+        );
+        assert_eq!(backend.request_count(), 2);
+
+        backend.set_grant_on_request(false);
+        assert_eq!(
+            manager
+                .request_permission(Permission::Accessibility)
+                .unwrap(),
+            PermissionStatus::Denied
+        );
+    }
+
     #[test]
     fn test_framework_availability() {
         let manager = PhotoKitPermissionManager::new(None);
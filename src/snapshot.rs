@@ -0,0 +1,583 @@
+//! 权限快照
+//!
+//! `PermissionSnapshot` 记录某一时刻所有已启用权限的授权状态，
+//! 由 `check_all_permissions` 命令一次性返回，并可以与另一份快照比较得到变化列表。
+//!
+//! `SnapshotStore` 把快照以 JSON 保存在应用数据目录中。应用关闭期间用户可能在系统设置中撤销了授权，
+//! 插件在启动时把当前状态与上次保存的快照比较，以 `startupReconciliation` 为原因发送变化事件。
+
+use crate::{
+    Permission, PermissionStatus, PhotoKitListenerError, PhotoKitPermissionListener,
+    PhotoKitPermissionManager,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Runtime;
+
+/// 每项权限检查的默认超时时间
+///
+/// 完全磁盘访问权限的检查需要读取目录，在网络挂载的主目录上可能很慢。
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 应用数据目录中保存权限快照的文件名
+pub const SNAPSHOT_FILE_NAME: &str = "macos-permissions-snapshot.json";
+
+/// 快照读写错误
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotStoreError {
+    #[error("快照文件读写失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("快照格式无效: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("事件发送失败: {0}")]
+    Listener(#[from] PhotoKitListenerError),
+    #[error("快照锁定失败")]
+    LockFailed,
+}
+
+/// 某一时刻所有已启用权限的授权状态
+///
+/// 序列化后 `statuses` 为 `{ permission, status }` 列表，因为 PhotoKit 权限不能作为 JSON 对象的键。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionSnapshot {
+    /// 每项权限的授权状态
+    #[serde(
+        serialize_with = "serialize_statuses",
+        deserialize_with = "deserialize_statuses"
+    )]
+    pub statuses: HashMap<Permission, PermissionStatus>,
+
+    /// 在超时时间内没有返回结果的权限
+    #[serde(rename = "timedOut")]
+    pub timed_out: Vec<Permission>,
+
+    /// 检查失败的权限
+    pub failed: Vec<Permission>,
+
+    /// 快照的采集时间（Unix 时间戳，毫秒）
+    #[serde(rename = "capturedAt")]
+    pub captured_at: u64,
+}
+
+/// 两份快照之间某项权限的状态变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PermissionChange {
+    /// 发生变化的权限
+    pub permission: Permission,
+
+    /// 旧快照中的状态，旧快照中没有该权限时为 None
+    pub previous: Option<PermissionStatus>,
+
+    /// 新快照中的状态，新快照中没有该权限时为 None
+    pub current: Option<PermissionStatus>,
+}
+
+impl PermissionSnapshot {
+    /// 获取指定权限在快照中的状态
+    pub fn get(&self, permission: Permission) -> Option<PermissionStatus> {
+        self.statuses.get(&permission).copied()
+    }
+
+    /// 比较两份快照，返回状态不同的权限
+    ///
+    /// 结果按 `Permission::ALL` 的顺序排列。
+    ///
+    /// # Arguments
+    /// * `newer` - 较新的快照
+    pub fn diff(&self, newer: &PermissionSnapshot) -> Vec<PermissionChange> {
+        Permission::ALL
+            .iter()
+            .filter_map(|&permission| {
+                let previous = self.get(permission);
+                let current = newer.get(permission);
+
+                (previous != current).then_some(PermissionChange {
+                    permission,
+                    previous,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    /// 为本次超时或检查失败的权限沿用旧快照中的状态
+    ///
+    /// 保存的快照因此不会因为一次慢速检查而丢失某项权限，下次启动仍然可以比较。
+    ///
+    /// # Arguments
+    /// * `older` - 较旧的快照
+    pub fn carry_over(&mut self, older: &PermissionSnapshot) {
+        for &permission in self.timed_out.iter().chain(&self.failed) {
+            if let Some(status) = older.get(permission) {
+                self.statuses.entry(permission).or_insert(status);
+            }
+        }
+    }
+}
+
+/// 权限快照的 JSON 文件存储
+///
+/// 克隆的存储共享同一份内存中的快照。
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    path: PathBuf,
+    /// 最近一次保存的快照，在第一次 `update` 之前为 None
+    current: Arc<Mutex<Option<PermissionSnapshot>>>,
+}
+
+impl SnapshotStore {
+    /// 创建保存到指定文件的快照存储
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            current: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 快照文件的路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 读取上次保存的快照
+    ///
+    /// # Returns
+    /// 文件不存在时返回 None
+    pub fn load(&self) -> Result<Option<PermissionSnapshot>, SnapshotStoreError> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 保存快照
+    ///
+    /// 先写入临时文件再重命名，避免应用在写入过程中退出时留下不完整的文件。
+    pub fn save(&self, snapshot: &PermissionSnapshot) -> Result<(), SnapshotStoreError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_vec_pretty(snapshot)?)?;
+        std::fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// 用新快照替换上次保存的快照
+    ///
+    /// 无法解析的旧文件视为不存在并被覆盖。
+    ///
+    /// # Returns
+    /// 上次保存的快照
+    pub fn update(
+        &self,
+        mut snapshot: PermissionSnapshot,
+    ) -> Result<Option<PermissionSnapshot>, SnapshotStoreError> {
+        let previous = match self.load() {
+            Err(SnapshotStoreError::Serialization(_)) => None,
+            result => result?,
+        };
+
+        if let Some(previous) = &previous {
+            snapshot.carry_over(previous);
+        }
+        self.save(&snapshot)?;
+
+        *self
+            .current
+            .lock()
+            .map_err(|_| SnapshotStoreError::LockFailed)? = Some(snapshot);
+        Ok(previous)
+    }
+
+    /// 把运行期间观察到的权限状态写入保存的快照
+    ///
+    /// 插件不在退出时检查和保存，因此每次状态变化都立即写入，应用崩溃或被强制退出后快照仍是最新的，
+    /// 下次启动不会把本次运行中已经分发过的变化再报告一次。
+    /// 在第一次 `update` 之前不写入，避免覆盖启动时还没有比较的上次快照。
+    ///
+    /// # Arguments
+    /// * `permission` - 被检查的权限
+    /// * `status` - 观察到的状态
+    /// * `observed_at` - 观察到状态的时间（Unix 时间戳，毫秒）
+    pub fn record_status(
+        &self,
+        permission: Permission,
+        status: PermissionStatus,
+        observed_at: u64,
+    ) -> Result<(), SnapshotStoreError> {
+        let mut current = self
+            .current
+            .lock()
+            .map_err(|_| SnapshotStoreError::LockFailed)?;
+        let Some(snapshot) = current.as_mut() else {
+            return Ok(());
+        };
+        if snapshot.get(permission) == Some(status) {
+            return Ok(());
+        }
+
+        snapshot.statuses.insert(permission, status);
+        snapshot.timed_out.retain(|&p| p != permission);
+        snapshot.failed.retain(|&p| p != permission);
+        snapshot.captured_at = observed_at;
+        self.save(snapshot)
+    }
+}
+
+/// 把当前的权限状态与上次保存的快照比较，为每项变化发送事件，并保存当前状态
+///
+/// 只比较两份快照中都有状态的权限：第一次运行、新启用的权限以及本次检查超时的权限都不发送事件。
+/// 事件以 `startupReconciliation` 为原因，通过 `PhotoKitPermissionListener::reconcile_status` 分发。
+///
+/// # Returns
+/// 发送了事件的状态变化
+pub fn reconcile_saved_snapshot<R: Runtime>(
+    manager: &PhotoKitPermissionManager,
+    listener: &PhotoKitPermissionListener<R>,
+    store: &SnapshotStore,
+) -> Result<Vec<PermissionChange>, SnapshotStoreError> {
+    let current = manager.check_all_permissions(PROBE_TIMEOUT);
+    let Some(previous) = store.update(current.clone())? else {
+        return Ok(Vec::new());
+    };
+
+    let changes: Vec<PermissionChange> = previous
+        .diff(&current)
+        .into_iter()
+        .filter(|change| change.previous.is_some() && change.current.is_some())
+        .collect();
+
+    for change in &changes {
+        if let (Some(previous), Some(current)) = (change.previous, change.current) {
+            listener.reconcile_status(change.permission, previous, current)?;
+        }
+    }
+
+    Ok(changes)
+}
+
+/// 把状态映射序列化为按 `Permission::ALL` 排序的 `{ permission, status }` 列表
+fn serialize_statuses<S: Serializer>(
+    statuses: &HashMap<Permission, PermissionStatus>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Entry {
+        permission: Permission,
+        status: PermissionStatus,
+    }
+
+    serializer.collect_seq(Permission::ALL.iter().filter_map(|&permission| {
+        statuses
+            .get(&permission)
+            .map(|&status| Entry { permission, status })
+    }))
+}
+
+/// 从 `{ permission, status }` 列表反序列化状态映射
+fn deserialize_statuses<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<Permission, PermissionStatus>, D::Error> {
+    #[derive(Deserialize)]
+    struct Entry {
+        permission: Permission,
+        status: PermissionStatus,
+    }
+
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| (entry.permission, entry.status))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        init_with_backend, ChangeCause, FakePermissionBackend, MacosPermissionsExt,
+        PhotoKitAccessLevel,
+    };
+    use std::sync::{Arc, Mutex};
+    use tauri::test::{mock_builder, mock_context, noop_assets};
+
+    /// 每个测试使用独立的临时目录，测试结束时删除
+    struct TempStore {
+        dir: PathBuf,
+        store: SnapshotStore,
+    }
+
+    impl TempStore {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("snapshot-{}", uuid::Uuid::new_v4()));
+            let store = SnapshotStore::new(dir.join(SNAPSHOT_FILE_NAME));
+            Self { dir, store }
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn snapshot(statuses: &[(Permission, PermissionStatus)]) -> PermissionSnapshot {
+        PermissionSnapshot {
+            statuses: statuses.iter().copied().collect(),
+            timed_out: Vec::new(),
+            failed: Vec::new(),
+            captured_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let read = Permission::PhotoKit(PhotoKitAccessLevel::Read);
+        let older = snapshot(&[
+            (Permission::Camera, PermissionStatus::NotDetermined),
+            (Permission::Microphone, PermissionStatus::Denied),
+            (read, PermissionStatus::Limited),
+        ]);
+        let newer = snapshot(&[
+            (Permission::Camera, PermissionStatus::Authorized),
+            (Permission::Microphone, PermissionStatus::Denied),
+            (Permission::Accessibility, PermissionStatus::Authorized),
+        ]);
+
+        assert_eq!(
+            older.diff(&newer),
+            vec![
+                PermissionChange {
+                    permission: Permission::Accessibility,
+                    previous: None,
+                    current: Some(PermissionStatus::Authorized),
+                },
+                PermissionChange {
+                    permission: Permission::Camera,
+                    previous: Some(PermissionStatus::NotDetermined),
+                    current: Some(PermissionStatus::Authorized),
+                },
+                PermissionChange {
+                    permission: read,
+                    previous: Some(PermissionStatus::Limited),
+                    current: None,
+                },
+            ]
+        );
+        assert!(newer.diff(&newer).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_serialization() {
+        let mut snapshot = snapshot(&[
+            (
+                Permission::PhotoKit(PhotoKitAccessLevel::AddOnly),
+                PermissionStatus::Authorized,
+            ),
+            (Permission::Camera, PermissionStatus::Denied),
+        ]);
+        snapshot.timed_out.push(Permission::FullDiskAccess);
+        snapshot.captured_at = 42;
+
+        assert_eq!(
+            serde_json::to_value(&snapshot).unwrap(),
+            serde_json::json!({
+                "statuses": [
+                    { "permission": "camera", "status": "denied" },
+                    { "permission": { "photoKit": "addOnly" }, "status": "authorized" }
+                ],
+                "timedOut": ["fullDiskAccess"],
+                "failed": [],
+                "capturedAt": 42
+            })
+        );
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let temp = TempStore::new();
+        assert!(temp.store.load().unwrap().is_none());
+
+        let mut saved = snapshot(&[
+            (Permission::Camera, PermissionStatus::Authorized),
+            (
+                Permission::PhotoKit(PhotoKitAccessLevel::Read),
+                PermissionStatus::Limited,
+            ),
+        ]);
+        saved.failed.push(Permission::FullDiskAccess);
+        temp.store.save(&saved).unwrap();
+        assert_eq!(temp.store.load().unwrap(), Some(saved));
+
+        // 无法解析的文件在更新时被覆盖
+        std::fs::write(temp.store.path(), "not json").unwrap();
+        assert!(matches!(
+            temp.store.load(),
+            Err(SnapshotStoreError::Serialization(_))
+        ));
+        let current = snapshot(&[(Permission::Camera, PermissionStatus::Denied)]);
+        assert_eq!(temp.store.update(current.clone()).unwrap(), None);
+        assert_eq!(temp.store.load().unwrap(), Some(current));
+    }
+
+    #[test]
+    fn test_update_carries_over_missing_statuses() {
+        let temp = TempStore::new();
+        temp.store
+            .save(&snapshot(&[
+                (Permission::Camera, PermissionStatus::Authorized),
+                (Permission::FullDiskAccess, PermissionStatus::Authorized),
+            ]))
+            .unwrap();
+
+        let mut current = snapshot(&[(Permission::Camera, PermissionStatus::Denied)]);
+        current.timed_out.push(Permission::FullDiskAccess);
+        temp.store.update(current).unwrap();
+
+        let saved = temp.store.load().unwrap().unwrap();
+        assert_eq!(
+            saved.get(Permission::Camera),
+            Some(PermissionStatus::Denied)
+        );
+        assert_eq!(
+            saved.get(Permission::FullDiskAccess),
+            Some(PermissionStatus::Authorized)
+        );
+    }
+
+    #[test]
+    fn test_reconcile_saved_snapshot() {
+        let temp = TempStore::new();
+        let backend = Arc::new(FakePermissionBackend::new());
+        let app = mock_builder()
+            .plugin(init_with_backend(backend.clone()))
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+        let permissions = app.macos_permissions();
+
+        // 第一次运行只保存快照
+        backend.set_status(Permission::Camera, PermissionStatus::Authorized);
+        let changes =
+            reconcile_saved_snapshot(permissions.manager(), permissions.listener(), &temp.store)
+                .unwrap();
+        assert!(changes.is_empty());
+        assert!(temp.store.load().unwrap().is_some());
+
+        // 应用关闭期间用户撤销了授权，下次启动时订阅者先收到当前状态
+        backend.set_status(Permission::Camera, PermissionStatus::Denied);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = received.clone();
+        let _guard = permissions
+            .on_change(Permission::Camera, move |event| {
+                events.lock().unwrap().push(event.clone());
+            })
+            .unwrap();
+        let changes =
+            reconcile_saved_snapshot(permissions.manager(), permissions.listener(), &temp.store)
+                .unwrap();
+        assert_eq!(
+            changes,
+            vec![PermissionChange {
+                permission: Permission::Camera,
+                previous: Some(PermissionStatus::Authorized),
+                current: Some(PermissionStatus::Denied),
+            }]
+        );
+
+        let events = received.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].cause, ChangeCause::Initial);
+        assert_eq!(events[1].cause, ChangeCause::StartupReconciliation);
+        assert_eq!(
+            events[1].previous_status,
+            Some(PermissionStatus::Authorized)
+        );
+        assert_eq!(events[1].status, PermissionStatus::Denied);
+
+        let history = permissions
+            .listener()
+            .event_history(0, Some(Permission::Camera))
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].cause, ChangeCause::StartupReconciliation);
+
+        let saved = temp.store.load().unwrap().unwrap();
+        assert_eq!(
+            saved.get(Permission::Camera),
+            Some(PermissionStatus::Denied)
+        );
+
+        // 之后注册的监听器先收到启动时发现的变化，再收到当前状态
+        let late = Arc::new(Mutex::new(Vec::new()));
+        let causes = late.clone();
+        let _late_guard = permissions
+            .on_change(Permission::Camera, move |event| {
+                causes.lock().unwrap().push(event.cause);
+            })
+            .unwrap();
+        assert_eq!(
+            *late.lock().unwrap(),
+            vec![ChangeCause::StartupReconciliation, ChangeCause::Initial]
+        );
+    }
+
+    #[test]
+    fn test_observed_changes_are_saved() {
+        let temp = TempStore::new();
+        let app = mock_builder()
+            .build(mock_context(noop_assets()))
+            .expect("failed to build mock app");
+        let listener = PhotoKitPermissionListener::with_event_targets(app.handle().clone(), vec![])
+            .with_snapshot_store(temp.store.clone());
+        listener
+            .register_permission_listener(Permission::Camera)
+            .unwrap();
+
+        // 启动时的快照还没有比较，不写入
+        listener
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::Denied,
+                ChangeCause::ExternalChange,
+            )
+            .unwrap();
+        assert!(temp.store.load().unwrap().is_none());
+
+        let mut startup = snapshot(&[(Permission::Camera, PermissionStatus::Denied)]);
+        startup.timed_out.push(Permission::Microphone);
+        temp.store.update(startup).unwrap();
+
+        listener
+            .observe_status(
+                Permission::Camera,
+                PermissionStatus::Authorized,
+                ChangeCause::ExternalChange,
+            )
+            .unwrap();
+        // 没有监听器关注的权限同样写入
+        listener
+            .observe_status(
+                Permission::Microphone,
+                PermissionStatus::Authorized,
+                ChangeCause::UserRequest,
+            )
+            .unwrap();
+
+        let saved = temp.store.load().unwrap().unwrap();
+        assert_eq!(
+            saved.get(Permission::Camera),
+            Some(PermissionStatus::Authorized)
+        );
+        assert_eq!(
+            saved.get(Permission::Microphone),
+            Some(PermissionStatus::Authorized)
+        );
+        assert!(saved.timed_out.is_empty());
+    }
+}